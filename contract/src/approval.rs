@@ -53,6 +53,12 @@ impl NonFungibleTokenCore for Contract {
             "Predecessor must be the token owner."
         );
 
+        //soulbound tokens can't be transferred so there is nothing to approve
+        require!(
+            !self.is_token_soulbound(&token),
+            "Soulbound tokens cannot be approved"
+        );
+
         //get the next approval ID if we need a new approval
         let approval_id: u64 = token.next_approval_id;
 
@@ -77,8 +83,9 @@ impl NonFungibleTokenCore for Contract {
         //insert the token back into the tokens_by_id collection
         self.tokens_by_id.insert(&token_id, &token);
 
-        //refund any excess storage attached by the user. If the user didn't attach enough, panic.
-        refund_deposit(storage_used);
+        //charge the storage to the owner, from the attached deposit first and then its storage balance.
+        //any excess attached by the user is refunded
+        self.internal_charge_storage(&token.owner_id, storage_used);
 
         //if some message was passed into the function, we initiate a cross contract call on the
         //account we're giving access to.
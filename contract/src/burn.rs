@@ -1,11 +1,17 @@
 use ed25519_dalek::Verifier;
 
-use crate::{nft_core::NonFungibleTokenCore, *};
+use crate::*;
 
 /// CUSTOM - owner can burn a locked token for a given user, reducing the enumerable->nft_supply_for_type
 #[near_bindgen]
 impl Contract {
-    pub fn nft_burn(&mut self, token_id: TokenId, owner_public_key: String, signature: Vec<u8>) {
+    pub fn nft_burn(
+        &mut self,
+        token_id: TokenId,
+        owner_public_key: String,
+        signature: Vec<u8>,
+        nonce: Option<u64>,
+    ) {
         let public_key = ed25519_dalek::PublicKey::from_bytes(
             &bs58::decode(owner_public_key).into_vec().unwrap(),
         )
@@ -24,19 +30,25 @@ impl Contract {
             owner_id = signer_acct;
         }
         //ensure owner actually signed
-        let current_owner_nonce = self.get_nonce(&owner_id);
-        let owner_next_nonce = current_owner_nonce + 1;
-        let next_nonce_hash = env::sha256(format!("{}", owner_next_nonce).as_bytes());
+        //any unused nonce can be signed, defaults to the one after the highest used
+        let owner_nonce = nonce.unwrap_or_else(|| self.get_nonce(&owner_id) + 1);
+        let nonce_hash = env::sha256(format!("{}", owner_nonce).as_bytes());
         let signature = ed25519_dalek::Signature::try_from(signature.as_ref())
             .expect("Signature should be a valid array of 64 bytes [13, 254, 123, ...]");
 
-        if let Ok(_) = public_key.verify(&next_nonce_hash, &signature) {
-            self.burn_helper(token_id, owner_id);
+        if let Ok(_) = public_key.verify(&nonce_hash, &signature) {
+            self.internal_use_nonce(&owner_id, NonceScope::OWNER, owner_nonce);
+            if let (Some(payer_id), released_storage) = self.burn_helper(token_id, owner_id) {
+                self.internal_refund_storage(&payer_id, released_storage);
+            }
         } else {
             panic!("Unauthorized: invalid signature");
         }
     }
 
+    /// Burn `amount` tokens of a series, 10 by default. The owner either calls it directly with `named_owner_id`,
+    /// or is the implicit account of `owner_public_key` and signed the nonce.
+    /// Burns as many tokens as the attached gas allows. If some are left, returns a job ID to finish with `continue_job`.
     pub fn batch_burn(
         &mut self,
         series_id: SeriesId,
@@ -44,7 +56,8 @@ impl Contract {
         owner_public_key: String,
         signature: Vec<u8>,
         named_owner_id: Option<AccountId>,
-    ) {
+        nonce: Option<u64>,
+    ) -> Option<u64> {
         // derive publicKey
         let public_key = ed25519_dalek::PublicKey::from_bytes(
             &bs58::decode(owner_public_key).into_vec().unwrap(),
@@ -61,67 +74,51 @@ impl Contract {
             AccountId::new_unchecked(hex::encode(public_key))
         };
 
-        let tokens_for_owner_set = self.tokens_per_owner.get(&owner_id);
+        //nothing to burn
+        if self
+            .owner_nft_tokens_for_series_count(series_id, owner_id.clone())
+            .0
+            == 0
+        {
+            return None;
+        }
 
-        let tokens_to_burn = if let Some(tokens_for_owner_set) = tokens_for_owner_set {
-            let tokens = tokens_for_owner_set
-                .iter()
-                .map(|token_id| self.nft_token(token_id.clone()).unwrap())
-                .filter(|token| token.series_id == series_id)
-                .take(amount.unwrap_or(10) as usize)
-                .collect();
-            tokens
-        } else {
-            vec![]
-        };
+        //if owner_id is caller, no other permission is needed
+        //otherwise, ensure that caller has permission to burn
+        if owner_id != env::predecessor_account_id() {
+            //any unused nonce can be signed, defaults to the one after the highest used
+            let owner_nonce = nonce.unwrap_or_else(|| self.get_nonce(&owner_id) + 1);
+            let nonce_hash = env::sha256(format!("{}", owner_nonce).as_bytes());
 
-        if tokens_to_burn.len() > 0 {
-            //get the first token property
-            let token_0 = &tokens_to_burn[0];
-
-            //only the owner should be able to burn
-            if owner_id != token_0.owner_id {
-                env::panic_str("Unauthorized: wrong owner");
-            };
-            let current_owner_nonce = self.get_nonce(&owner_id);
-            let owner_next_nonce = current_owner_nonce + 1;
-
-            if owner_id == env::predecessor_account_id() {
-                //if owner_id is caller, no other permission is needed
-                for token in tokens_to_burn {
-                    self.burn_helper(token.token_id, owner_id.clone());
-                }
-            } else {
-                //otherwise, ensure that caller has permission to burn
-
-                let next_nonce_hash = env::sha256(format!("{}", owner_next_nonce).as_bytes());
-
-                let signature = ed25519_dalek::Signature::try_from(signature.as_ref())
-                    .expect("Signature should be a valid array of 64 bytes [13, 254, 123, ...]");
-
-                if let Ok(_) = public_key.verify(&next_nonce_hash, &signature) {
-                    for token in tokens_to_burn {
-                        self.burn_helper(token.token_id, owner_id.clone());
-                    }
-                } else {
-                    panic!("Unauthorized: invalid signature");
-                }
-            }
+            let signature = ed25519_dalek::Signature::try_from(signature.as_ref())
+                .expect("Signature should be a valid array of 64 bytes [13, 254, 123, ...]");
 
-            //set new nonce
-            self.nonces.insert(&owner_id, &owner_next_nonce);
+            if public_key.verify(&nonce_hash, &signature).is_err() {
+                panic!("Unauthorized: invalid signature");
+            }
+            self.internal_use_nonce(&owner_id, NonceScope::OWNER, owner_nonce);
         }
+
+        self.internal_start_job(Job {
+            kind: JobKind::BURN,
+            series_id,
+            owner_id,
+            receiver_id: None,
+            processed: 0,
+            remaining: amount.unwrap_or(10),
+            memo: None,
+        })
     }
-    fn burn_helper(&mut self, token_id: TokenId, owner_id: AccountId) {
-        let token = self.tokens_by_id.get(&token_id).expect("No token");
 
-        self.internal_transfer(
-            &owner_id,
-            &AccountId::new_unchecked((&"unrecoverable_burn_account").to_string()),
-            &token_id,
-            None,
-            None,
-        );
+    /// Burn a token of `owner_id` and return who paid for its storage and how many bytes it released,
+    /// for the caller to refund to the payer
+    pub(crate) fn burn_helper(
+        &mut self,
+        token_id: TokenId,
+        owner_id: AccountId,
+    ) -> (Option<AccountId>, u64) {
+        self.assert_not_paused(PauseFeature::BURN);
+        let released_storage = self.internal_burn(&token_id);
 
         let nft_burn_log: EventLog = EventLog {
             // Standard name ("nep171").
@@ -131,11 +128,39 @@ impl Contract {
             // The data related with the event stored in a vector.
             event: EventLogVariant::NftBurn(vec![NftBurnLog {
                 // owner's account ID.
-                owner_id: token.owner_id.to_string(),
+                owner_id: owner_id.to_string(),
                 // A vector containing the token IDs as strings.
                 token_ids: vec![token_id.to_string()],
             }]),
         };
         env::log_str(&nft_burn_log.to_string());
+
+        released_storage
+    }
+
+    /// Delete a token everywhere it is tracked and return who paid for its storage, if anyone did
+    /// but the contract, and how many bytes of storage were released.
+    /// Burned editions still count towards the series copies, so they are never minted again.
+    pub(crate) fn internal_burn(&mut self, token_id: &TokenId) -> (Option<AccountId>, u64) {
+        let initial_storage_usage = env::storage_usage();
+        let token = self.tokens_by_id.get(token_id).expect("No token");
+
+        self.internal_remove_token_from_owner(&token.owner_id, token_id);
+        self.tokens_by_id.remove(token_id);
+        let payer_id = self.token_storage_payers.remove(token_id);
+
+        let mut series = self
+            .series_by_id
+            .get(&token.series_id)
+            .expect("Not a series");
+        series.tokens.remove(token_id);
+        series.burned += 1;
+        self.series_by_id.insert(&token.series_id, &series);
+        self.total_burned += 1;
+
+        (
+            payer_id,
+            initial_storage_usage.saturating_sub(env::storage_usage()),
+        )
     }
 }
@@ -13,6 +13,38 @@ pub struct JsonSeries {
     pub owner_id: AccountId,
     //Type of the collection
     pub series_type: u8,
+    // Price of each token when bought through `nft_mint_paid`
+    pub price: Option<U128>,
+    // Account proposed as the new owner of the collection
+    pub pending_owner_id: Option<AccountId>,
+    // Lifecycle status of the collection: draft (1), active (2), paused (3) or closed (4)
+    pub status: u8,
+    // When minting opens, Unix epoch in milliseconds
+    pub mint_starts_at: Option<u64>,
+    // When minting closes, Unix epoch in milliseconds
+    pub mint_ends_at: Option<u64>,
+    // Whether the tokens of the collection are bound to their owner
+    pub soulbound: bool,
+    // How many tokens of the collection can be minted to a single account
+    pub max_per_account: Option<u64>,
+    // Whether every account can only get one token of the collection
+    pub unique: bool,
+    // Merkle root of the accounts allowed to mint through `mint_with_proof`
+    pub allowlist_root: Option<Base64VecU8>,
+    // How many tokens of the collection were burned
+    pub burned: u64,
+    // Whether the owner of the collection pays for the storage of minted tokens
+    pub storage_sponsored: bool,
+}
+
+/// Whether tokens can currently be minted from a series, and why not
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonSeriesMintability {
+    pub series_id: u64,
+    pub mintable: bool,
+    // Why minting is not possible, if it isn't
+    pub reason: Option<String>,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize)]
@@ -32,6 +64,30 @@ pub struct OwnerDashboardJson {
     //Type of the collection
     pub series_type: u8,
 }
+/// How many tokens of a series were minted, burned and are still in circulation
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonSupplyStats {
+    pub series_id: u64,
+    // Tokens ever minted, including the burned ones
+    pub minted: U128,
+    pub burned: U128,
+    // Tokens that currently exist
+    pub circulating: U128,
+}
+
+/// Token counts across every series of the contract
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonContractStats {
+    pub series_count: u64,
+    // Tokens ever minted, including the burned ones
+    pub minted: U128,
+    pub burned: U128,
+    // Tokens that currently exist
+    pub circulating: U128,
+}
+
 #[near_bindgen]
 impl Contract {
     //Query for the total supply of NFTs on the contract
@@ -139,6 +195,17 @@ impl Contract {
                 royalty: series.royalty,
                 owner_id: series.owner_id,
                 series_type: series.series_type.to_code(),
+                price: series.price.map(U128),
+                pending_owner_id: series.pending_owner_id,
+                status: series.status.to_code(),
+                mint_starts_at: series.mint_starts_at,
+                mint_ends_at: series.mint_ends_at,
+                soulbound: series.soulbound,
+                max_per_account: series.max_per_account,
+                unique: series.max_per_account == Some(1),
+                allowlist_root: series.allowlist_root.map(|root| root.to_vec().into()),
+                burned: series.burned,
+                storage_sponsored: series.storage_sponsored,
             })
         } else {
             //if there isn't a series, we'll return None
@@ -146,6 +213,81 @@ impl Contract {
         }
     }
 
+    // check if a series can currently be minted from, and the reason if it can't
+    pub fn get_series_mintability(&self, series_id: u64) -> JsonSeriesMintability {
+        let reason = if self.internal_is_paused(PauseFeature::MINT) {
+            Some(PauseFeature::MINT.paused_message().to_string())
+        } else if let Some(series) = self.series_by_id.get(&series_id) {
+            series.mint_blocker()
+        } else {
+            Some("Not a series".to_string())
+        };
+
+        JsonSeriesMintability {
+            series_id,
+            mintable: reason.is_none(),
+            reason,
+        }
+    }
+
+    // get how many more tokens of a series can be minted to an account, `None` if there is no limit
+    pub fn get_remaining_mint_allowance(
+        &self,
+        series_id: u64,
+        account_id: AccountId,
+    ) -> Option<u64> {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        series.max_per_account.map(|max_per_account| {
+            let minted = self.internal_minted_to_account(&series, &account_id);
+            max_per_account.saturating_sub(minted)
+        })
+    }
+
+    // get how many tokens of a series an account has claimed through the allowlist
+    pub fn get_allowlist_claimed(&self, series_id: u64, account_id: AccountId) -> u64 {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        series.allowlist_claimed.get(&account_id).unwrap_or(0)
+    }
+
+    // get how many claim codes of a series can still be redeemed
+    pub fn get_unclaimed_code_count(&self, series_id: u64) -> u64 {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        series.claim_codes.len()
+    }
+
+    // get the public keys that can sign mint vouchers for a series, besides the owner's key
+    pub fn get_series_minting_keys(&self, series_id: u64) -> Vec<String> {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        series.minting_keys.to_vec()
+    }
+
+    // get the accounts that can mint a series directly, besides the approved minters
+    pub fn get_series_minters(&self, series_id: u64) -> Vec<AccountId> {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        series.minters.to_vec()
+    }
+
+    /// Minted, burned and circulating counts of a series
+    pub fn series_supply_stats(&self, series_id: u64) -> JsonSupplyStats {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        JsonSupplyStats {
+            series_id,
+            minted: U128(series.minted as u128),
+            burned: U128(series.burned as u128),
+            circulating: U128((series.minted - series.burned) as u128),
+        }
+    }
+
+    /// Minted, burned and circulating counts across the whole contract
+    pub fn contract_stats(&self) -> JsonContractStats {
+        JsonContractStats {
+            series_count: self.series_by_id.len(),
+            minted: U128(self.total_minted as u128),
+            burned: U128(self.total_burned as u128),
+            circulating: U128((self.total_minted - self.total_burned) as u128),
+        }
+    }
+
     //get the total supply of NFTs on a current series
     pub fn nft_supply_for_series(&self, id: u64) -> U128 {
         //get the series
@@ -252,6 +394,7 @@ impl Contract {
                     royalty: _,
                     owner_id,
                     series_type,
+                    ..
                 } = json_series;
 
                 OwnerDashboardJson {
@@ -283,10 +426,38 @@ impl Contract {
             .collect()
     }
 
+    // Paginate through the series that are in the given lifecycle status
+    pub fn get_series_by_status(
+        &self,
+        status: u8,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonSeries> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.series_by_id
+            .keys()
+            .map(|series_id| self.get_series_details(series_id).unwrap())
+            .filter(|series| series.status == status)
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //highest nonce used by the account in any scope, kept for clients that sign `get_nonce() + 1`
     pub fn get_nonce(&self, account_id: &AccountId) -> u64 {
-        self.nonces.get(&account_id).unwrap_or(0)
+        [NonceScope::MINT, NonceScope::OWNER]
+            .iter()
+            .map(|scope| self.internal_highest_nonce(account_id, *scope))
+            .chain(self.legacy_nonces.get(account_id))
+            .max()
+            .unwrap_or(0)
     }
-    pub fn owner_public_key(&self) -> String {
-        self.owner_public_key.clone()
+    //the most recently registered signing key that is still active
+    pub fn owner_public_key(&self) -> Option<String> {
+        self.internal_active_signing_keys().pop()
     }
 }
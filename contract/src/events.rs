@@ -13,6 +13,23 @@ pub enum EventLogVariant {
     NftMint(Vec<NftMintLog>),
     NftTransfer(Vec<NftTransferLog>),
     NftBurn(Vec<NftBurnLog>),
+    NftMetadataUpdate(Vec<NftMetadataUpdateLog>),
+    SeriesOwnerProposed(Vec<SeriesOwnerProposalLog>),
+    SeriesOwnerProposalCancelled(Vec<SeriesOwnerProposalLog>),
+    SeriesOwnerTransferred(Vec<SeriesOwnerTransferLog>),
+    SeriesStatusUpdate(Vec<SeriesStatusLog>),
+    SeriesMetadataUpdate(Vec<SeriesMetadataUpdateLog>),
+    SeriesMintingKeyAdded(Vec<SeriesMintingKeyLog>),
+    SeriesMintingKeyRemoved(Vec<SeriesMintingKeyLog>),
+    SigningKeyAdded(Vec<SigningKeyLog>),
+    SigningKeyRetired(Vec<SigningKeyLog>),
+    SigningKeyExpiryUpdate(Vec<SigningKeyLog>),
+    ContractPauseUpdate(Vec<ContractPauseLog>),
+    RoleGranted(Vec<RoleLog>),
+    RoleRevoked(Vec<RoleLog>),
+    ContractOwnerProposed(Vec<ContractOwnerProposalLog>),
+    ContractOwnerProposalCancelled(Vec<ContractOwnerProposalLog>),
+    ContractOwnerTransferred(Vec<ContractOwnerTransferLog>),
 }
 
 /// Interface to capture data about an event
@@ -68,6 +85,150 @@ pub struct NftBurnLog {
     pub owner_id: String,
     pub token_ids: Vec<String>,
 }
+/// An event log to capture token metadata changes
+///
+/// Arguments
+/// * `token_ids`: ["1", "abc"]
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftMetadataUpdateLog {
+    pub token_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture a proposed change of series owner
+///
+/// Arguments
+/// * `series_id`: 1
+/// * `owner_id`: "creator.near"
+/// * `proposed_owner_id`: "brand.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesOwnerProposalLog {
+    pub series_id: u64,
+    pub owner_id: String,
+    pub proposed_owner_id: String,
+}
+
+/// An event log to capture a series changing owner
+///
+/// Arguments
+/// * `series_id`: 1
+/// * `old_owner_id`: "creator.near"
+/// * `new_owner_id`: "brand.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesOwnerTransferLog {
+    pub series_id: u64,
+    pub old_owner_id: String,
+    pub new_owner_id: String,
+}
+
+/// An event log to capture a series moving through its lifecycle
+///
+/// Arguments
+/// * `series_id`: 1
+/// * `old_status`: 1 (draft)
+/// * `new_status`: 2 (active)
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesStatusLog {
+    pub series_id: u64,
+    pub old_status: u8,
+    pub new_status: u8,
+}
+
+/// An event log to capture the metadata of a series changing, which changes the metadata of all its tokens
+///
+/// Arguments
+/// * `series_id`: 1
+/// * `token_count`: number of tokens in the series
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesMetadataUpdateLog {
+    pub series_id: u64,
+    pub token_count: u64,
+}
+
+/// An event log to capture a minting key being registered or removed for a series
+///
+/// Arguments
+/// * `series_id`: 1
+/// * `public_key`: "8QoJVEQAstCiSU4osfagAMZQqUpoYnvj1K8kgczhSE4e"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesMintingKeyLog {
+    pub series_id: u64,
+    pub public_key: String,
+}
+
+/// An event log to capture the lifecycle of a key that signs mint vouchers
+///
+/// Arguments
+/// * `key_id`: "default"
+/// * `public_key`: "8QoJVEQAstCiSU4osfagAMZQqUpoYnvj1K8kgczhSE4e"
+/// * `expires_at`: optional expiry, Unix epoch in milliseconds
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SigningKeyLog {
+    pub key_id: String,
+    pub public_key: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+/// An event log to capture a feature of the contract being paused or resumed
+///
+/// Arguments
+/// * `feature`: 1 (minting)
+/// * `paused`: true
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractPauseLog {
+    pub feature: u8,
+    pub paused: bool,
+}
+
+/// An event log to capture a role being granted to or revoked from an account
+///
+/// Arguments
+/// * `role`: 3 (minter)
+/// * `account_id`: "minter.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoleLog {
+    pub role: u8,
+    pub account_id: String,
+}
+
+/// An event log to capture a proposal to hand the contract over to a new owner
+///
+/// Arguments
+/// * `owner_id`: "gateway.near"
+/// * `proposed_owner_id`: "new-gateway.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractOwnerProposalLog {
+    pub owner_id: String,
+    pub proposed_owner_id: String,
+}
+
+/// An event log to capture the contract changing owner
+///
+/// Arguments
+/// * `old_owner_id`: "gateway.near"
+/// * `new_owner_id`: "new-gateway.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractOwnerTransferLog {
+    pub old_owner_id: String,
+    pub new_owner_id: String,
+}
+
 /// An event log to capture token transfer
 ///
 /// Arguments
@@ -131,6 +292,49 @@ mod tests {
         assert_eq!(expected, log.to_string());
     }
 
+    #[test]
+    fn nep_format_metadata_update() {
+        let expected = r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_metadata_update","data":[{"token_ids":["1:1","1:2"]}]}"#;
+        let log = EventLog {
+            standard: "nep171".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::NftMetadataUpdate(vec![NftMetadataUpdateLog {
+                token_ids: vec!["1:1".to_string(), "1:2".to_string()],
+                memo: None,
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+
+    #[test]
+    fn gateway_format_series_owner_transferred() {
+        let expected = r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"series_owner_transferred","data":[{"series_id":1,"old_owner_id":"creator.near","new_owner_id":"brand.near"}]}"#;
+        let log = EventLog {
+            standard: "gateway".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::SeriesOwnerTransferred(vec![SeriesOwnerTransferLog {
+                series_id: 1,
+                old_owner_id: "creator.near".to_string(),
+                new_owner_id: "brand.near".to_string(),
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+
+    #[test]
+    fn gateway_format_series_metadata_update() {
+        let expected = r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"series_metadata_update","data":[{"series_id":1,"token_count":250}]}"#;
+        let log = EventLog {
+            standard: "gateway".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::SeriesMetadataUpdate(vec![SeriesMetadataUpdateLog {
+                series_id: 1,
+                token_count: 250,
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+
     #[test]
     fn nep_format_transfer_all_fields() {
         let expected = r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_transfer","data":[{"authorized_id":"market.near","old_owner_id":"user1.near","new_owner_id":"user2.near","token_ids":["token"],"memo":"Go Team!"}]}"#;
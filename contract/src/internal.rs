@@ -47,6 +47,29 @@ pub(crate) fn hash_account_id(account_id: &String) -> CryptoHash {
     hash
 }
 
+//decode a base58 ed25519 public key, with or without the "ed25519:" prefix
+pub(crate) fn parse_public_key(public_key: &str) -> ed25519_dalek::PublicKey {
+    let public_key = public_key.strip_prefix("ed25519:").unwrap_or(public_key);
+    let bytes = bs58::decode(public_key)
+        .into_vec()
+        .expect("Public key should be base58 encoded");
+    ed25519_dalek::PublicKey::from_bytes(&bytes).expect("Public key should be a valid ed25519 key")
+}
+
+//hash two merkle nodes together. Pairs are sorted first so proofs don't need to carry the position of each node
+pub(crate) fn hash_merkle_pair(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    env::sha256(&[first, second].concat())
+}
+
+//check that the leaf is part of the merkle tree with the given root
+pub(crate) fn verify_merkle_proof(leaf: Vec<u8>, proof: &[Base64VecU8], root: &CryptoHash) -> bool {
+    let computed_root = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_merkle_pair(&node, &sibling.0));
+    computed_root.as_slice() == root
+}
+
 //used to make sure the user attached exactly 1 yoctoNEAR
 pub(crate) fn assert_one_yocto() {
     assert_eq!(
@@ -64,7 +87,7 @@ pub(crate) fn assert_at_least_one_yocto() {
     )
 }
 
-// Send all the non storage funds to the series owner
+// Send the price to the series owner and refund whatever was attached on top of price and storage
 pub(crate) fn payout_series_owner(
     storage_used: u64,
     price_per_token: Balance,
@@ -83,28 +106,13 @@ pub(crate) fn payout_series_owner(
         price_per_token
     );
 
-    // If there's a price for the token, transfer everything but the storage to the series owner
+    // If there's a price for the token, transfer it to the series owner
     if price_per_token > 0 {
-        Promise::new(owner_id).transfer(attached_deposit - required_cost);
+        Promise::new(owner_id).transfer(price_per_token);
     }
-}
-
-//refund the initial deposit based on the amount of storage that was used up
-pub(crate) fn refund_deposit(storage_used: u64) {
-    //get how much it would cost to store the information
-    let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
-    //get the attached deposit
-    let attached_deposit = env::attached_deposit();
 
-    //make sure that the attached deposit is greater than or equal to the required cost
-    assert!(
-        required_cost <= attached_deposit,
-        "Must attach {} yoctoNEAR to cover storage",
-        required_cost,
-    );
-
-    //get the refund amount from the attached deposit - required cost
-    let refund = attached_deposit - required_cost;
+    //get the refund amount from the attached deposit - required cost - price
+    let refund = attached_deposit - required_cost - price_per_token;
 
     //if the refund is greater than 1 yocto NEAR, we refund the predecessor that amount
     if refund > 1 {
@@ -112,6 +120,43 @@ pub(crate) fn refund_deposit(storage_used: u64) {
     }
 }
 
+//log nft_mint events with one entry per owner, chunked so a log holds at most MAX_TOKEN_IDS_PER_LOG token IDs
+pub(crate) fn log_nft_mints(mint_logs: Vec<NftMintLog>) {
+    let mut chunk: Vec<NftMintLog> = vec![];
+    let mut chunk_token_count = 0;
+    for mint_log in mint_logs {
+        for token_ids in mint_log.token_ids.chunks(MAX_TOKEN_IDS_PER_LOG) {
+            if chunk_token_count + token_ids.len() > MAX_TOKEN_IDS_PER_LOG {
+                log_nft_mint_chunk(std::mem::take(&mut chunk));
+                chunk_token_count = 0;
+            }
+            chunk_token_count += token_ids.len();
+            chunk.push(NftMintLog {
+                owner_id: mint_log.owner_id.clone(),
+                token_ids: token_ids.to_vec(),
+                memo: mint_log.memo.clone(),
+            });
+        }
+    }
+    if !chunk.is_empty() {
+        log_nft_mint_chunk(chunk);
+    }
+}
+
+fn log_nft_mint_chunk(mint_logs: Vec<NftMintLog>) {
+    let nft_mint_log: EventLog = EventLog {
+        // Standard name ("nep171").
+        standard: NFT_STANDARD_NAME.to_string(),
+        // Version of the standard ("nft-1.0.0").
+        version: NFT_METADATA_SPEC.to_string(),
+        // The data related with the event stored in a vector.
+        event: EventLogVariant::NftMint(mint_logs),
+    };
+
+    // Log the serialized json.
+    env::log_str(&nft_mint_log.to_string());
+}
+
 impl Contract {
     /// Ensure that the caller is the owner of the contract
     pub(crate) fn assert_contract_owner(&mut self) {
@@ -121,6 +166,74 @@ impl Contract {
         )
     }
 
+    /// Ensure that the caller is the owner of the series or of the contract
+    pub(crate) fn assert_series_owner(&self, series: &Series) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == series.owner_id || caller == self.owner_id,
+            "only the series owner can update the series"
+        );
+    }
+
+    /// Ensure that the caller is the owner of the series or of the contract, or a moderator
+    pub(crate) fn assert_series_moderator(&self, series: &Series) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == series.owner_id
+                || caller == self.owner_id
+                || self.internal_has_role(Role::MODERATOR, &caller),
+            "only the series owner can update the series"
+        );
+    }
+
+    /// Ensure that the caller can mint the series directly, either as an approved minter or a minter of the series
+    pub(crate) fn assert_series_minter(&self, series: &Series) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.owner_id
+                || self.internal_has_role(Role::MINTER, &caller)
+                || series.minters.contains(&caller),
+            "only approved minters can mint"
+        );
+    }
+
+    //log a series_metadata_update event covering every token of the series, and nft_metadata_update events
+    //listing all of them, chunked like the mint logs, unless the series is too large to list
+    pub(crate) fn internal_log_series_metadata_update(&self, series_id: SeriesId, series: &Series) {
+        let series_metadata_update_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SeriesMetadataUpdate(vec![SeriesMetadataUpdateLog {
+                series_id,
+                token_count: series.tokens.len(),
+            }]),
+        };
+        env::log_str(&series_metadata_update_log.to_string());
+
+        if series.tokens.len() > (MAX_TOKEN_IDS_PER_LOG * MAX_METADATA_UPDATE_LOGS) as u64 {
+            return;
+        }
+        let token_ids: Vec<String> = series.tokens.iter().collect();
+        for chunk in token_ids.chunks(MAX_TOKEN_IDS_PER_LOG) {
+            let nft_metadata_update_log: EventLog = EventLog {
+                // Standard name ("nep171").
+                standard: NFT_STANDARD_NAME.to_string(),
+                // Version of the standard ("nft-1.0.0").
+                version: NFT_METADATA_SPEC.to_string(),
+                // The data related with the event stored in a vector.
+                event: EventLogVariant::NftMetadataUpdate(vec![NftMetadataUpdateLog {
+                    // Vector of token IDs whose metadata changed.
+                    token_ids: chunk.to_vec(),
+                    // An optional memo to include.
+                    memo: None,
+                }]),
+            };
+
+            // Log the serialized json.
+            env::log_str(&nft_metadata_update_log.to_string());
+        }
+    }
+
     //add a token to the set of tokens an owner has
     pub(crate) fn internal_add_token_to_owner(
         &mut self,
@@ -190,8 +303,12 @@ impl Contract {
             .unwrap();
         current_owner_tokens_per_series.insert(&token.series_id, &(current_series_count - 1));
 
-        //if the token set is now empty, we remove the owner from the tokens_per_owner collection
+        //if the token set is now empty, we remove the owner from the tokens_per_owner collection.
+        //the inner collections are cleared too, they are recreated under the same prefix
+        //if the owner receives tokens again
         if tokens_set.is_empty() {
+            tokens_set.clear();
+            current_owner_tokens_per_series.clear();
             self.tokens_per_owner.remove(account_id);
             self.owner_tokens_per_series.remove(account_id);
         } else {
@@ -202,7 +319,26 @@ impl Contract {
         }
     }
 
+    //how many tokens of the series were minted to the account, tokens it received through transfers
+    //don't count. Mints from before the count was tracked aren't included
+    pub(crate) fn internal_minted_to_account(
+        &self,
+        series: &Series,
+        account_id: &AccountId,
+    ) -> u64 {
+        series.minted_per_account.get(account_id).unwrap_or(0)
+    }
+
+    //check if the token belongs to a soulbound series
+    pub(crate) fn is_token_soulbound(&self, token: &Token) -> bool {
+        self.series_by_id
+            .get(&token.series_id)
+            .expect("Not a series")
+            .soulbound
+    }
+
     //transfers the NFT to the receiver_id (internal method and can't be called directly via CLI).
+    //soulbound tokens cannot be transferred.
     pub(crate) fn internal_transfer(
         &mut self,
         sender_id: &AccountId,
@@ -211,6 +347,27 @@ impl Contract {
         //we introduce an approval ID so that people with that approval ID can transfer the token
         approval_id: Option<u64>,
         memo: Option<String>,
+    ) -> Token {
+        self.assert_not_paused(PauseFeature::TRANSFER);
+        let token = self.tokens_by_id.get(token_id).expect("No token");
+        require!(
+            !self.is_token_soulbound(&token),
+            "Soulbound tokens cannot be transferred"
+        );
+
+        self.internal_transfer_unrestricted(sender_id, receiver_id, token_id, approval_id, memo)
+    }
+
+    //transfers the NFT to the receiver_id regardless of the series transfer policy.
+    //only used for burning and recovering tokens.
+    pub(crate) fn internal_transfer_unrestricted(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        //we introduce an approval ID so that people with that approval ID can transfer the token
+        approval_id: Option<u64>,
+        memo: Option<String>,
     ) -> Token {
         //get the token object by passing in the token_id
         let token = self.tokens_by_id.get(token_id).expect("No token");
@@ -0,0 +1,216 @@
+use near_sdk::json_types::U128;
+use near_sdk::Gas;
+
+use crate::*;
+
+/// Gas set aside to process a single item of a bulk job
+const GAS_PER_JOB_ITEM: Gas = Gas(5_000_000_000_000);
+/// Gas kept to log the processed items and save the job once the loop stops
+const GAS_FOR_JOB_CHECKPOINT: Gas = Gas(10_000_000_000_000);
+
+// Represents the bulk operation a job carries out
+#[derive(BorshDeserialize, BorshSerialize, PartialEq, Clone, Copy)]
+pub enum JobKind {
+    MINT = 1,     //1
+    BURN = 2,     //2
+    WITHDRAW = 3, //3
+}
+
+impl JobKind {
+    pub fn to_code(&self) -> u8 {
+        match self {
+            JobKind::MINT => 1,
+            JobKind::BURN => 2,
+            JobKind::WITHDRAW => 3,
+        }
+    }
+}
+
+/// A bulk operation that didn't fit in the gas of a single call.
+/// The operation was authorized when the job was created, so any account can continue it.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Job {
+    pub kind: JobKind,
+    pub series_id: SeriesId,
+    // Account the tokens are minted to, or burned or withdrawn from
+    pub owner_id: AccountId,
+    // Account withdrawn tokens are sent to
+    pub receiver_id: Option<AccountId>,
+    // Number of items processed so far
+    pub processed: u64,
+    // Number of items left to process
+    pub remaining: u64,
+    // Memo included in the logs of the processed items
+    pub memo: Option<String>,
+}
+
+//The Json job is what will be returned from view calls.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonJob {
+    pub job_id: u64,
+    pub kind: u8,
+    pub series_id: SeriesId,
+    pub owner_id: AccountId,
+    pub receiver_id: Option<AccountId>,
+    pub processed: u64,
+    pub remaining: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Process the next items of a job that ran out of gas.
+    /// Returns the job ID again if there are items left, `None` once the job is done.
+    pub fn continue_job(&mut self, job_id: u64) -> Option<u64> {
+        let job = self.jobs.get(&job_id).expect("No job");
+        self.internal_run_job(Some(job_id), job)
+    }
+
+    /// Drop a job that can't or shouldn't finish, e.g. a mint job for a series that was closed.
+    /// Items that were already processed stay processed. Only the contract owner or an admin can cancel a job.
+    pub fn cancel_job(&mut self, job_id: u64) {
+        self.assert_role(Role::ADMIN);
+        require!(self.jobs.remove(&job_id).is_some(), "No job");
+    }
+
+    /// Get a job that still has items left
+    pub fn get_job(&self, job_id: u64) -> Option<JsonJob> {
+        self.jobs.get(&job_id).map(|job| job_to_json(job_id, job))
+    }
+
+    /// Paginate through the jobs that still have items left
+    pub fn get_pending_jobs(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<JsonJob> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.jobs
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .map(|(job_id, job)| job_to_json(job_id, job))
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Process as many items of a new job as the attached gas allows.
+    /// Returns the job ID to continue with if not every item could be processed.
+    pub(crate) fn internal_start_job(&mut self, job: Job) -> Option<u64> {
+        self.internal_run_job(None, job)
+    }
+
+    fn internal_run_job(&mut self, job_id: Option<u64>, mut job: Job) -> Option<u64> {
+        let processed_before = job.processed;
+        let mut minted_token_ids = vec![];
+        //bytes released by burned tokens, by the account that paid for them
+        let mut released_storage: HashMap<AccountId, u64> = HashMap::new();
+        let initial_storage_usage = env::storage_usage();
+        //the owner's tokens are scanned once per run, not once per item
+        let mut token_ids = match job.kind {
+            JobKind::MINT => vec![],
+            JobKind::BURN | JobKind::WITHDRAW => self.internal_tokens_for_job(&job),
+        }
+        .into_iter();
+
+        while job.remaining > 0
+            && env::prepaid_gas() - env::used_gas() > GAS_PER_JOB_ITEM + GAS_FOR_JOB_CHECKPOINT
+        {
+            match job.kind {
+                JobKind::MINT => {
+                    minted_token_ids.push(self.internal_mint(job.series_id, job.owner_id.clone()))
+                }
+                JobKind::BURN | JobKind::WITHDRAW => {
+                    // the owner may hold fewer tokens of the series than requested
+                    let token_id = match token_ids.next() {
+                        Some(token_id) => token_id,
+                        None => {
+                            job.remaining = 0;
+                            break;
+                        }
+                    };
+                    if job.kind == JobKind::BURN {
+                        if let (Some(payer_id), bytes) =
+                            self.burn_helper(token_id, job.owner_id.clone())
+                        {
+                            *released_storage.entry(payer_id).or_default() += bytes;
+                        }
+                    } else {
+                        self.assert_not_paused(PauseFeature::WITHDRAW);
+                        let receiver_id = job.receiver_id.clone().expect("No receiver");
+                        self.internal_transfer(&job.owner_id, &receiver_id, &token_id, None, None);
+                    }
+                }
+            }
+            job.processed += 1;
+            job.remaining -= 1;
+        }
+        require!(
+            job.remaining == 0 || job.processed > processed_before,
+            "Not enough gas attached to process any item"
+        );
+
+        if !minted_token_ids.is_empty() {
+            log_nft_mints(vec![NftMintLog {
+                owner_id: job.owner_id.to_string(),
+                token_ids: minted_token_ids.clone(),
+                memo: job.memo.clone(),
+            }]);
+            self.internal_charge_mint_storage(
+                job.series_id,
+                &job.owner_id,
+                &minted_token_ids,
+                initial_storage_usage,
+            );
+        }
+
+        for (payer_id, bytes) in released_storage {
+            self.internal_refund_storage(&payer_id, bytes);
+        }
+
+        if job.remaining == 0 {
+            if let Some(job_id) = job_id {
+                self.jobs.remove(&job_id);
+            }
+            return None;
+        }
+
+        //save the cursor so the caller can continue where we stopped
+        let job_id = job_id.unwrap_or_else(|| {
+            let job_id = self.next_job_id;
+            self.next_job_id += 1;
+            job_id
+        });
+        self.jobs.insert(&job_id, &job);
+        Some(job_id)
+    }
+
+    //up to `remaining` tokens of the job's series that the job's owner still holds
+    fn internal_tokens_for_job(&self, job: &Job) -> Vec<TokenId> {
+        let tokens = match self.tokens_per_owner.get(&job.owner_id) {
+            Some(tokens) => tokens,
+            None => return vec![],
+        };
+        tokens
+            .iter()
+            .filter(|token_id| {
+                let token = self.tokens_by_id.get(token_id);
+                matches!(token, Some(token) if token.series_id == job.series_id)
+            })
+            .take(job.remaining as usize)
+            .collect()
+    }
+}
+
+fn job_to_json(job_id: u64, job: Job) -> JsonJob {
+    JsonJob {
+        job_id,
+        kind: job.kind.to_code(),
+        series_id: job.series_id,
+        owner_id: job.owner_id,
+        receiver_id: job.receiver_id,
+        processed: job.processed,
+        remaining: job.remaining,
+    }
+}
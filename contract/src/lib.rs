@@ -12,26 +12,53 @@ pub use crate::approval::*;
 pub use crate::burn::*;
 pub use crate::events::*;
 use crate::internal::*;
+pub use crate::jobs::*;
 pub use crate::metadata::*;
+pub use crate::migrate::*;
 pub use crate::nft_core::*;
+pub use crate::nonces::*;
 pub use crate::owner::*;
+pub use crate::pause::*;
+pub use crate::roles::*;
 pub use crate::royalty::*;
 pub use crate::series::*;
+pub use crate::signing_keys::*;
+pub use crate::storage::*;
+pub use crate::voucher::*;
 
 mod approval;
 mod burn;
 mod enumeration;
 mod events;
 mod internal;
+mod jobs;
 mod metadata;
+mod migrate;
 mod nft_core;
+mod nonces;
 mod owner;
+mod pause;
+mod roles;
 mod royalty;
 mod series;
+mod signing_keys;
+mod storage;
+mod voucher;
 /// This spec can be treated like a version of the standard.
 pub const NFT_METADATA_SPEC: &str = "1.0.0";
 /// This is the name of the NFT standard we're using
 pub const NFT_STANDARD_NAME: &str = "nep171";
+/// This is the name of the standard used for the contract's own events
+pub const GATEWAY_STANDARD_NAME: &str = "gateway";
+/// Version of the contract's own events
+pub const GATEWAY_EVENT_VERSION: &str = "1.0.0";
+/// ID the signing key passed at initialization is registered under
+pub const DEFAULT_SIGNING_KEY_ID: &str = "default";
+/// Maximum number of token IDs included in a single event log
+pub const MAX_TOKEN_IDS_PER_LOG: usize = 100;
+/// Maximum number of nft_metadata_update logs a series metadata update emits. Larger series
+/// only get the series_metadata_update event, reading every token would cost too much gas.
+pub const MAX_METADATA_UPDATE_LOGS: usize = 5;
 
 // Represents the series type. All tokens will derive this data.
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -48,6 +75,40 @@ pub struct Series {
     // Owner of the collection
     owner_id: AccountId,
     series_type: SeriesType,
+    // Account proposed as the new owner, waiting for it to accept
+    pending_owner_id: Option<AccountId>,
+    // Lifecycle status, tokens can only be minted while the series is active
+    status: SeriesStatus,
+    // When minting opens, Unix epoch in milliseconds. Independent of the tokens' `starts_at`
+    mint_starts_at: Option<u64>,
+    // When minting closes, Unix epoch in milliseconds. Independent of the tokens' `expires_at`
+    mint_ends_at: Option<u64>,
+    // Soulbound tokens stay with the account they were minted to, they can only be burned or recovered by the contract owner
+    soulbound: bool,
+    // How many tokens of the series can be minted to a single account. 1 makes the series unique per account
+    max_per_account: Option<u64>,
+    // How many tokens of the series were minted to each account, regardless of whether they still hold them
+    minted_per_account: LookupMap<AccountId, u64>,
+    // Merkle root of the accounts allowed to mint through `mint_with_proof`
+    allowlist_root: Option<CryptoHash>,
+    // How many tokens each account has claimed through the allowlist
+    allowlist_claimed: LookupMap<AccountId, u64>,
+    // sha256 hashes of the one-time claim codes that can still be redeemed
+    claim_codes: UnorderedSet<CryptoHash>,
+    // sha256 hashes of the claim codes that were already redeemed, so they can't be registered again
+    used_claim_codes: LookupSet<CryptoHash>,
+    // Base58 ed25519 public keys of the series owner that can sign mint vouchers for this series
+    minting_keys: UnorderedSet<String>,
+    // Accounts that can mint this series directly through `nft_mint`, besides the approved minters
+    minters: UnorderedSet<AccountId>,
+    // Edition of the next token minted, editions are never reused
+    next_edition: u64,
+    // How many tokens were ever minted from the series, including the burned ones
+    minted: u64,
+    // How many tokens of the series were burned
+    burned: u64,
+    // Whether the series owner pays for the storage of the tokens minted from the series
+    storage_sponsored: bool,
 }
 
 pub type SeriesId = u64;
@@ -75,24 +136,71 @@ impl SeriesType {
     }
 }
 
+// Represents the lifecycle status of a series.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq)]
+pub enum SeriesStatus {
+    DRAFT = 1,  //1
+    ACTIVE = 2, //2
+    PAUSED = 3, //3
+    CLOSED = 4, //4
+}
+
+impl SeriesStatus {
+    pub fn to_code(&self) -> u8 {
+        match self {
+            SeriesStatus::DRAFT => 1,
+            SeriesStatus::ACTIVE => 2,
+            SeriesStatus::PAUSED => 3,
+            SeriesStatus::CLOSED => 4,
+        }
+    }
+
+    pub fn from(val: u8) -> SeriesStatus {
+        match val {
+            1 => SeriesStatus::DRAFT,
+            2 => SeriesStatus::ACTIVE,
+            3 => SeriesStatus::PAUSED,
+            4 => SeriesStatus::CLOSED,
+            _ => panic!("Invalid Series Status"),
+        }
+    }
+
+    // A draft can be activated, an active series paused and a paused series resumed.
+    // Any series that isn't closed yet can be closed, closing is permanent.
+    pub fn can_transition_to(&self, next: &SeriesStatus) -> bool {
+        matches!(
+            (self, next),
+            (SeriesStatus::DRAFT, SeriesStatus::ACTIVE)
+                | (SeriesStatus::ACTIVE, SeriesStatus::PAUSED)
+                | (SeriesStatus::PAUSED, SeriesStatus::ACTIVE)
+                | (SeriesStatus::DRAFT, SeriesStatus::CLOSED)
+                | (SeriesStatus::ACTIVE, SeriesStatus::CLOSED)
+                | (SeriesStatus::PAUSED, SeriesStatus::CLOSED)
+        )
+    }
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
     //contract owner
     pub owner_id: AccountId,
 
-    //contract owner public key
-    pub owner_public_key: String,
+    //account proposed as the new contract owner, waiting for it to accept
+    pub pending_owner_id: Option<AccountId>,
 
-    //approved minters
-    pub approved_minters: LookupSet<AccountId>,
+    //keys that can sign mint vouchers on behalf of the contract owner, by key ID
+    pub signing_keys: UnorderedMap<String, SigningKey>,
 
-    //approved users that can create series
-    pub approved_creators: LookupSet<AccountId>,
+    //accounts each role was granted to, by role code
+    pub role_members: LookupMap<u8, UnorderedSet<AccountId>>,
 
     //Map the collection ID (stored in Token obj) to the collection data
     pub series_by_id: UnorderedMap<SeriesId, Series>,
 
+    //ID the next series will be created with, IDs are never reused
+    pub next_series_id: SeriesId,
+
     //keeps track of the token struct for a given token ID
     pub tokens_by_id: UnorderedMap<TokenId, Token>,
 
@@ -106,8 +214,35 @@ pub struct Contract {
     //keeps track of the metadata for the contract
     pub metadata: LazyOption<NFTContractMetadata>,
 
-    // map of nonces used to prevent replay attack;
-    pub nonces: LookupMap<AccountId, u64>,
+    // highest nonce each account used before nonces were scoped, it and every nonce below it count as used
+    pub legacy_nonces: LookupMap<AccountId, u64>,
+
+    // bitmaps of the nonces each account already used, by (account, scope, word)
+    pub used_nonces: LookupMap<(AccountId, u8, u64), u128>,
+
+    // highest nonce each account used, by (account, scope)
+    pub highest_nonces: LookupMap<(AccountId, u8), u64>,
+
+    //bulk operations that ran out of gas, by job ID
+    pub jobs: UnorderedMap<u64, Job>,
+
+    //ID the next saved job will get
+    pub next_job_id: u64,
+
+    //how many tokens were ever minted across all series, including the burned ones
+    pub total_minted: u64,
+
+    //how many tokens were burned across all series
+    pub total_burned: u64,
+
+    //NEAR deposited by each account to pay for the storage it uses
+    pub storage_deposits: LookupMap<AccountId, Balance>,
+
+    //account that paid for the storage of each token, refunded when the token is burned
+    pub token_storage_payers: LookupMap<TokenId, AccountId>,
+
+    //features of the contract that are currently paused, one bit per feature
+    pub paused: u8,
 }
 
 /// Helper structure for keys of the persistent collections.
@@ -124,6 +259,20 @@ pub enum StorageKey {
     TokensById,
     NFTContractMetadata,
     Nonces,
+    SeriesMintedPerAccount { series_id: SeriesId },
+    SeriesAllowlistClaimed { series_id: SeriesId },
+    SeriesClaimCodes { series_id: SeriesId },
+    SeriesUsedClaimCodes { series_id: SeriesId },
+    SeriesMintingKeys { series_id: SeriesId },
+    SigningKeys,
+    UsedNonces,
+    SeriesMinters { series_id: SeriesId },
+    Jobs,
+    StorageDeposits,
+    RoleMembers,
+    RoleMembersInner { role: u8 },
+    HighestNonces,
+    TokenStoragePayers,
 }
 
 #[near_bindgen]
@@ -166,21 +315,11 @@ impl Contract {
         owner_public_key: String,
         metadata: NFTContractMetadata,
     ) -> Self {
-        // Create the approved minters set and insert the owner
-        let mut approved_minters =
-            LookupSet::new(StorageKey::ApprovedMinters.try_to_vec().unwrap());
-        approved_minters.insert(&owner_id);
-
-        // Create the approved creators set and insert the owner
-        let mut approved_creators =
-            LookupSet::new(StorageKey::ApprovedCreators.try_to_vec().unwrap());
-        approved_creators.insert(&owner_id);
-
         // Create a variable of type Self with all the fields initialized.
-        let this = Self {
-            approved_minters,
-            approved_creators,
+        let mut this = Self {
+            role_members: LookupMap::new(StorageKey::RoleMembers.try_to_vec().unwrap()),
             series_by_id: UnorderedMap::new(StorageKey::SeriesById.try_to_vec().unwrap()),
+            next_series_id: 1,
             //Storage keys are simply the prefixes used for the collections. This helps avoid data collision
             tokens_per_owner: LookupMap::new(StorageKey::TokensPerOwner.try_to_vec().unwrap()),
             owner_tokens_per_series: UnorderedMap::new(
@@ -189,14 +328,37 @@ impl Contract {
             tokens_by_id: UnorderedMap::new(StorageKey::TokensById.try_to_vec().unwrap()),
             //set the &owner_id field equal to the passed in owner_id.
             owner_id,
-            owner_public_key,
+            pending_owner_id: None,
+            signing_keys: UnorderedMap::new(StorageKey::SigningKeys.try_to_vec().unwrap()),
             metadata: LazyOption::new(
                 StorageKey::NFTContractMetadata.try_to_vec().unwrap(),
                 Some(&metadata),
             ),
-            nonces: LookupMap::new(StorageKey::Nonces.try_to_vec().unwrap()),
+            legacy_nonces: LookupMap::new(StorageKey::Nonces.try_to_vec().unwrap()),
+            used_nonces: LookupMap::new(StorageKey::UsedNonces.try_to_vec().unwrap()),
+            highest_nonces: LookupMap::new(StorageKey::HighestNonces.try_to_vec().unwrap()),
+            jobs: UnorderedMap::new(StorageKey::Jobs.try_to_vec().unwrap()),
+            next_job_id: 1,
+            total_minted: 0,
+            total_burned: 0,
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits.try_to_vec().unwrap()),
+            token_storage_payers: LookupMap::new(
+                StorageKey::TokenStoragePayers.try_to_vec().unwrap(),
+            ),
+            paused: 0,
         };
 
+        //the owner starts out as an approved creator and minter
+        let owner_id = this.owner_id.clone();
+        this.internal_grant_role(Role::CREATOR, &owner_id);
+        this.internal_grant_role(Role::MINTER, &owner_id);
+
+        //the initial public key becomes the first key that can sign mint vouchers
+        this.internal_add_signing_key(DEFAULT_SIGNING_KEY_ID.to_string(), owner_public_key, None);
+
+        //fresh deployments start out in the current state layout
+        migrate::write_state_version();
+
         //return the Contract object
         this
     }
@@ -0,0 +1,243 @@
+#[cfg(any(target_arch = "wasm32", test))]
+use near_sdk::Gas;
+
+use crate::*;
+
+/// Layout version of the state written by this release. Bump it and keep the previous layout
+/// as a `VersionedContract` variant whenever the contract, a series or a token changes layout.
+pub const CURRENT_STATE_VERSION: u16 = 2;
+/// Storage key the layout version of the state is kept under
+const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+/// Gas kept by `upgrade` to deploy the code, the rest is attached to `migrate`
+#[cfg(any(target_arch = "wasm32", test))]
+const GAS_FOR_UPGRADE: Gas = Gas(20_000_000_000_000);
+
+/// Account burned tokens were transferred to before they were deleted for real
+const LEGACY_BURN_ACCOUNT_ID: &str = "unrecoverable_burn_account";
+
+// Series as stored by the first release of the contract
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LegacySeries {
+    pub metadata: TokenMetadata,
+    pub royalty: Option<HashMap<AccountId, u32>>,
+    pub tokens: UnorderedSet<TokenId>,
+    pub price: Option<Balance>,
+    pub owner_id: AccountId,
+    pub series_type: SeriesType,
+}
+
+// Contract state as stored by the first release of the contract
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LegacyContract {
+    pub owner_id: AccountId,
+    pub owner_public_key: String,
+    pub approved_minters: LookupSet<AccountId>,
+    pub approved_creators: LookupSet<AccountId>,
+    pub series_by_id: UnorderedMap<SeriesId, LegacySeries>,
+    pub tokens_by_id: UnorderedMap<TokenId, Token>,
+    pub tokens_per_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+    pub owner_tokens_per_series: UnorderedMap<AccountId, UnorderedMap<SeriesId, u64>>,
+    pub metadata: LazyOption<NFTContractMetadata>,
+    pub nonces: LookupMap<AccountId, u64>,
+}
+
+/// Contract state, in the layout of the release that wrote it
+pub enum VersionedContract {
+    // first release, which didn't record a version
+    V1(Box<LegacyContract>),
+    V2(Box<Contract>),
+}
+
+impl VersionedContract {
+    /// Read the contract state in the layout it was written in
+    pub fn read() -> Self {
+        let version = read_state_version();
+        match version {
+            1 => VersionedContract::V1(Box::new(env::state_read().expect("No state to migrate"))),
+            2 => VersionedContract::V2(Box::new(env::state_read().expect("No state to migrate"))),
+            _ => panic!("Unknown state version {}", version),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Migrate the state of any earlier release to the current layout, does nothing if it is already current.
+    /// Called by `upgrade` once the new code is deployed.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let this = match VersionedContract::read() {
+            VersionedContract::V1(old_state) => Self::migrate_from_v1(*old_state),
+            VersionedContract::V2(state) => *state,
+        };
+        write_state_version();
+        this
+    }
+
+    /// Layout version of the contract state
+    pub fn get_state_version(&self) -> u16 {
+        read_state_version()
+    }
+
+    /// Move accounts of the approved minters or creators of earlier releases to the minter (3) or creator (2) role.
+    /// The legacy sets can't be enumerated, so the accounts are passed in and checked against them.
+    /// Accounts that aren't in the legacy set are skipped. Returns how many accounts were moved.
+    pub fn migrate_legacy_roles(&mut self, role: u8, account_ids: Vec<AccountId>) -> u64 {
+        self.assert_contract_owner();
+
+        let role = Role::from(role);
+        let mut legacy_accounts: LookupSet<AccountId> = match role {
+            Role::MINTER => LookupSet::new(StorageKey::ApprovedMinters.try_to_vec().unwrap()),
+            Role::CREATOR => LookupSet::new(StorageKey::ApprovedCreators.try_to_vec().unwrap()),
+            _ => panic!("Only minters and creators can be migrated"),
+        };
+
+        let mut migrated = 0;
+        for account_id in account_ids {
+            if legacy_accounts.remove(&account_id) {
+                self.internal_grant_role(role, &account_id);
+                migrated += 1;
+            }
+        }
+        migrated
+    }
+
+    /// Delete up to `limit` tokens, 50 by default, that earlier releases parked in the burn account
+    /// instead of burning them. The released storage stays with the contract, which paid for it.
+    /// Call it again until it returns 0, the number of tokens still parked.
+    pub fn burn_parked_tokens(&mut self, limit: Option<u64>) -> u64 {
+        self.assert_contract_owner();
+
+        let burn_account_id = AccountId::new_unchecked(LEGACY_BURN_ACCOUNT_ID.to_string());
+        let parked_token_ids = match self.tokens_per_owner.get(&burn_account_id) {
+            Some(parked_token_ids) => parked_token_ids,
+            None => return 0,
+        };
+        let token_ids: Vec<TokenId> = parked_token_ids
+            .iter()
+            .take(limit.unwrap_or(50) as usize)
+            .collect();
+
+        //the burns were already logged when the tokens were parked
+        for token_id in token_ids.iter() {
+            self.internal_burn(token_id);
+        }
+
+        self.nft_supply_for_owner(burn_account_id).0 as u64
+    }
+}
+
+impl Contract {
+    /// Rewrite the state of the first release in the current layout.
+    /// Series IDs continue after the highest one in use and editions after the tokens already minted, so IDs are never reused.
+    /// Tokens kept their layout, they are read as is.
+    fn migrate_from_v1(old_state: LegacyContract) -> Self {
+        // Series are rewritten in the new layout under the same prefix. Their token sets are kept as is
+        let mut legacy_series_by_id = old_state.series_by_id;
+        let legacy_series = legacy_series_by_id.to_vec();
+        legacy_series_by_id.clear();
+
+        let mut series_by_id = UnorderedMap::new(StorageKey::SeriesById.try_to_vec().unwrap());
+        let mut next_series_id = 1;
+        for (series_id, legacy_series) in legacy_series {
+            //the first release numbered editions by the size of the token set and never removed tokens from it
+            let next_edition = legacy_series.tokens.len() + 1;
+
+            let mut series = Series::new(
+                series_id,
+                legacy_series.metadata,
+                legacy_series.royalty,
+                legacy_series.tokens,
+                legacy_series.price,
+                legacy_series.owner_id,
+                legacy_series.series_type,
+            );
+            series.next_edition = next_edition;
+            series.minted = series.tokens.len();
+            series_by_id.insert(&series_id, &series);
+            next_series_id = std::cmp::max(next_series_id, series_id + 1);
+        }
+
+        //tokens parked in the burn account are counted as burned once `burn_parked_tokens` deletes them
+        let total_minted = old_state.tokens_by_id.len();
+
+        let mut this = Self {
+            owner_id: old_state.owner_id,
+            pending_owner_id: None,
+            signing_keys: UnorderedMap::new(StorageKey::SigningKeys.try_to_vec().unwrap()),
+            role_members: LookupMap::new(StorageKey::RoleMembers.try_to_vec().unwrap()),
+            series_by_id,
+            next_series_id,
+            tokens_by_id: old_state.tokens_by_id,
+            tokens_per_owner: old_state.tokens_per_owner,
+            owner_tokens_per_series: old_state.owner_tokens_per_series,
+            metadata: old_state.metadata,
+            //signatures of the first release only covered the nonce, so the ones it consumed must never be accepted again
+            legacy_nonces: old_state.nonces,
+            used_nonces: LookupMap::new(StorageKey::UsedNonces.try_to_vec().unwrap()),
+            highest_nonces: LookupMap::new(StorageKey::HighestNonces.try_to_vec().unwrap()),
+            jobs: UnorderedMap::new(StorageKey::Jobs.try_to_vec().unwrap()),
+            next_job_id: 1,
+            total_minted,
+            total_burned: 0,
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits.try_to_vec().unwrap()),
+            //the contract paid for the storage of legacy tokens and keeps it when they are burned
+            token_storage_payers: LookupMap::new(
+                StorageKey::TokenStoragePayers.try_to_vec().unwrap(),
+            ),
+            paused: 0,
+        };
+
+        //the legacy public key keeps signing vouchers until it is rotated
+        this.internal_add_signing_key(
+            DEFAULT_SIGNING_KEY_ID.to_string(),
+            old_state.owner_public_key,
+            None,
+        );
+
+        this
+    }
+
+    /// Deploy `code` on the contract account, then migrate the state with the new code
+    #[cfg(any(target_arch = "wasm32", test))]
+    pub(crate) fn internal_upgrade(&mut self, code: Vec<u8>) -> Promise {
+        self.assert_contract_owner();
+        require!(!code.is_empty(), "No code to deploy");
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call(
+                "migrate".to_string(),
+                b"{}".to_vec(),
+                0,
+                env::prepaid_gas() - env::used_gas() - GAS_FOR_UPGRADE,
+            )
+    }
+}
+
+/// Owner only. Deploy the wasm passed as the raw input of the call and migrate the state with it.
+/// The code isn't JSON encoded so that the whole transaction fits the wasm.
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn upgrade() {
+    env::setup_panic_hook();
+    let mut contract: Contract = env::state_read().expect("Contract is not initialized");
+    let code = env::input().expect("No code to deploy");
+    contract.internal_upgrade(code).as_return();
+}
+
+//layout version of the stored state, the first release didn't record one
+fn read_state_version() -> u16 {
+    env::storage_read(STATE_VERSION_KEY)
+        .map(|version| u16::try_from_slice(&version).expect("Invalid state version"))
+        .unwrap_or(1)
+}
+
+//record that the state is in the current layout
+pub(crate) fn write_state_version() {
+    env::storage_write(
+        STATE_VERSION_KEY,
+        &CURRENT_STATE_VERSION.try_to_vec().unwrap(),
+    );
+}
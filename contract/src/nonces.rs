@@ -0,0 +1,122 @@
+use crate::*;
+
+/// Number of nonces tracked by a single bitmap word
+const NONCES_PER_WORD: u64 = 128;
+/// How many nonces below the highest one an account used can still be consumed.
+/// The window is count based, not time based: an unused signature stays valid until the
+/// account consumes a nonce more than `NONCE_WINDOW` above it, however long that takes.
+/// Older nonces are pruned, which keeps at most a few bitmap words per account and scope.
+pub const NONCE_WINDOW: u64 = 1024;
+
+// Signed operations draw their nonces from separate scopes, so a mint voucher
+// and a withdraw or burn signature for the same account never compete for a nonce.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub enum NonceScope {
+    MINT = 1,  //1
+    OWNER = 2, //2
+}
+
+impl NonceScope {
+    pub fn to_code(&self) -> u8 {
+        match self {
+            NonceScope::MINT => 1,
+            NonceScope::OWNER => 2,
+        }
+    }
+
+    pub fn from(val: u8) -> NonceScope {
+        match val {
+            1 => NonceScope::MINT,
+            2 => NonceScope::OWNER,
+            _ => panic!("Invalid Nonce Scope"),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Whether a nonce can no longer be used by the account, because it was consumed or expired.
+    /// Scope 1 covers mint vouchers, scope 2 withdrawals and burns signed by the token owner.
+    pub fn is_nonce_used(&self, account_id: AccountId, scope: u8, nonce: u64) -> bool {
+        let scope = NonceScope::from(scope);
+        if nonce <= self.internal_nonce_floor(&account_id, scope) {
+            return true;
+        }
+        let (key, bit) = nonce_position(&account_id, scope, nonce);
+        self.used_nonces.get(&key).unwrap_or(0) & bit != 0
+    }
+}
+
+impl Contract {
+    /// Mark a nonce of the account as used. Nonces can be consumed in any order, but only once.
+    /// A nonce expires once the account used one more than `NONCE_WINDOW` above it.
+    pub(crate) fn internal_use_nonce(
+        &mut self,
+        account_id: &AccountId,
+        scope: NonceScope,
+        nonce: u64,
+    ) {
+        require!(nonce > 0, "Nonce should be greater than 0");
+        require!(
+            nonce > self.internal_nonce_floor(account_id, scope),
+            format!("Nonce {} was already used or expired", nonce)
+        );
+
+        let (key, bit) = nonce_position(account_id, scope, nonce);
+        let word = self.used_nonces.get(&key).unwrap_or(0);
+        require!(word & bit == 0, format!("Nonce {} was already used", nonce));
+        self.used_nonces.insert(&key, &(word | bit));
+
+        let highest = self.internal_highest_nonce(account_id, scope);
+        if nonce > highest {
+            self.highest_nonces
+                .insert(&(account_id.clone(), scope.to_code()), &nonce);
+            self.internal_prune_nonces(account_id, scope, highest, nonce);
+        }
+    }
+
+    /// Highest nonce the account used in the scope
+    pub(crate) fn internal_highest_nonce(&self, account_id: &AccountId, scope: NonceScope) -> u64 {
+        self.highest_nonces
+            .get(&(account_id.clone(), scope.to_code()))
+            .unwrap_or(0)
+    }
+
+    //every nonce at or below the floor counts as used: the legacy ones and those that expired
+    fn internal_nonce_floor(&self, account_id: &AccountId, scope: NonceScope) -> u64 {
+        let expired = self
+            .internal_highest_nonce(account_id, scope)
+            .saturating_sub(NONCE_WINDOW);
+        std::cmp::max(expired, self.legacy_nonces.get(account_id).unwrap_or(0))
+    }
+
+    //remove the bitmap words that only hold nonces which expired when the highest nonce moved up
+    fn internal_prune_nonces(
+        &mut self,
+        account_id: &AccountId,
+        scope: NonceScope,
+        old_highest: u64,
+        new_highest: u64,
+    ) {
+        //words below the old window were already removed, words above the old highest nonce were never written
+        let first_word = old_highest.saturating_sub(NONCE_WINDOW) / NONCES_PER_WORD;
+        let expired_words = (new_highest.saturating_sub(NONCE_WINDOW) + 1) / NONCES_PER_WORD;
+        let last_word = std::cmp::min(expired_words, old_highest / NONCES_PER_WORD + 1);
+        for word in first_word..last_word {
+            self.used_nonces
+                .remove(&(account_id.clone(), scope.to_code(), word));
+        }
+    }
+}
+
+//the bitmap word a nonce lives in and the bit that represents it
+fn nonce_position(
+    account_id: &AccountId,
+    scope: NonceScope,
+    nonce: u64,
+) -> ((AccountId, u8, u64), u128) {
+    (
+        (account_id.clone(), scope.to_code(), nonce / NONCES_PER_WORD),
+        1u128 << (nonce % NONCES_PER_WORD),
+    )
+}
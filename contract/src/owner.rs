@@ -2,37 +2,128 @@ use crate::*;
 
 #[near_bindgen]
 impl Contract {
-    /// Add a specified account as an approved minter
+    /// Add a specified account as an approved minter, same as granting it the minter role
     pub fn add_approved_minter(&mut self, account_id: AccountId) {
-        self.assert_contract_owner();
-        self.approved_minters.insert(&account_id);
+        self.grant_role(Role::MINTER.to_code(), account_id);
     }
 
-    /// Remove a specified account as an approved minter
+    /// Remove a specified account as an approved minter, same as revoking its minter role
     pub fn remove_approved_minter(&mut self, account_id: AccountId) {
-        self.assert_contract_owner();
-        self.approved_minters.remove(&account_id);
+        self.revoke_role(Role::MINTER.to_code(), account_id);
     }
 
     /// Check if a specified account is an approved minter
     pub fn is_approved_minter(&self, account_id: AccountId) -> bool {
-        self.approved_minters.contains(&account_id)
+        self.internal_has_role(Role::MINTER, &account_id)
     }
 
-    /// Add a specified account as an approved creator
+    /// Add a specified account as an approved creator, same as granting it the creator role
     pub fn add_approved_creator(&mut self, account_id: AccountId) {
-        self.assert_contract_owner();
-        self.approved_creators.insert(&account_id);
+        self.grant_role(Role::CREATOR.to_code(), account_id);
     }
 
-    /// Remove a specified account as an approved creator
+    /// Remove a specified account as an approved creator, same as revoking its creator role
     pub fn remove_approved_creator(&mut self, account_id: AccountId) {
-        self.assert_contract_owner();
-        self.approved_creators.remove(&account_id);
+        self.revoke_role(Role::CREATOR.to_code(), account_id);
     }
 
     /// Check if a specified account is an approved creator
     pub fn is_approved_creator(&self, account_id: AccountId) -> bool {
-        self.approved_creators.contains(&account_id)
+        self.internal_has_role(Role::CREATOR, &account_id)
+    }
+
+    /// Propose a new owner for the contract. The ownership only changes once the proposed account
+    /// calls `accept_contract_owner`. Only the contract owner can propose.
+    pub fn propose_contract_owner(&mut self, new_owner_id: AccountId) {
+        self.assert_contract_owner();
+        require!(
+            new_owner_id != self.owner_id,
+            "the proposed owner already owns the contract"
+        );
+
+        self.pending_owner_id = Some(new_owner_id.clone());
+
+        self.log_contract_owner_event(EventLogVariant::ContractOwnerProposed(vec![
+            ContractOwnerProposalLog {
+                owner_id: self.owner_id.to_string(),
+                proposed_owner_id: new_owner_id.to_string(),
+            },
+        ]));
+    }
+
+    /// Accept the ownership of the contract. Must be called by the proposed owner.
+    /// The previous owner loses every role it holds and the new owner becomes a creator and minter.
+    /// If `public_key` is passed, it replaces the signing keys: every active key is retired
+    /// and the new key is registered under `${owner_id}@${block_height}`.
+    pub fn accept_contract_owner(&mut self, public_key: Option<String>) {
+        let caller = env::predecessor_account_id();
+        require!(
+            self.pending_owner_id.as_ref() == Some(&caller),
+            "only the proposed owner can accept the contract"
+        );
+
+        let old_owner_id = std::mem::replace(&mut self.owner_id, caller);
+        self.pending_owner_id = None;
+
+        //the previous owner was made a creator and minter with the contract, it keeps no access
+        for role in ROLES {
+            self.internal_revoke_role(role, &old_owner_id);
+        }
+        let owner_id = self.owner_id.clone();
+        self.internal_grant_role(Role::CREATOR, &owner_id);
+        self.internal_grant_role(Role::MINTER, &owner_id);
+
+        if let Some(public_key) = public_key {
+            for key_id in self.internal_active_signing_key_ids() {
+                self.internal_retire_signing_key(key_id);
+            }
+            let key_id = format!("{}@{}", self.owner_id, env::block_height());
+            self.internal_add_signing_key(key_id, public_key, None);
+        }
+
+        self.log_contract_owner_event(EventLogVariant::ContractOwnerTransferred(vec![
+            ContractOwnerTransferLog {
+                old_owner_id: old_owner_id.to_string(),
+                new_owner_id: self.owner_id.to_string(),
+            },
+        ]));
+    }
+
+    /// Cancel a pending contract ownership proposal. Only the contract owner can cancel.
+    pub fn cancel_owner_proposal(&mut self) {
+        self.assert_contract_owner();
+
+        let proposed_owner_id = self
+            .pending_owner_id
+            .take()
+            .expect("No pending owner for the contract");
+
+        self.log_contract_owner_event(EventLogVariant::ContractOwnerProposalCancelled(vec![
+            ContractOwnerProposalLog {
+                owner_id: self.owner_id.to_string(),
+                proposed_owner_id: proposed_owner_id.to_string(),
+            },
+        ]));
+    }
+
+    /// Get the owner of the contract
+    pub fn get_contract_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    /// Get the account proposed as the new owner of the contract, if any
+    pub fn get_pending_contract_owner(&self) -> Option<AccountId> {
+        self.pending_owner_id.clone()
+    }
+}
+
+impl Contract {
+    fn log_contract_owner_event(&self, event: EventLogVariant) {
+        let contract_owner_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event,
+        };
+        env::log_str(&contract_owner_log.to_string());
     }
 }
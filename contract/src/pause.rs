@@ -0,0 +1,114 @@
+use crate::*;
+
+// The features of the contract that can be paused during an incident
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum PauseFeature {
+    MINT = 1,          //1
+    TRANSFER = 2,      //2
+    BURN = 3,          //3
+    WITHDRAW = 4,      //4
+    CREATE_SERIES = 5, //5
+}
+
+impl PauseFeature {
+    pub fn to_code(&self) -> u8 {
+        match self {
+            PauseFeature::MINT => 1,
+            PauseFeature::TRANSFER => 2,
+            PauseFeature::BURN => 3,
+            PauseFeature::WITHDRAW => 4,
+            PauseFeature::CREATE_SERIES => 5,
+        }
+    }
+
+    pub fn from(val: u8) -> PauseFeature {
+        match val {
+            1 => PauseFeature::MINT,
+            2 => PauseFeature::TRANSFER,
+            3 => PauseFeature::BURN,
+            4 => PauseFeature::WITHDRAW,
+            5 => PauseFeature::CREATE_SERIES,
+            _ => panic!("Invalid Pause Feature"),
+        }
+    }
+
+    // bit of the feature in the contract's pause flags
+    fn flag(&self) -> u8 {
+        1 << self.to_code()
+    }
+
+    pub(crate) fn paused_message(&self) -> &'static str {
+        match self {
+            PauseFeature::MINT => "Minting is paused",
+            PauseFeature::TRANSFER => "Transfers are paused",
+            PauseFeature::BURN => "Burning is paused",
+            PauseFeature::WITHDRAW => "Withdrawals are paused",
+            PauseFeature::CREATE_SERIES => "Series creation is paused",
+        }
+    }
+}
+
+//The Json pause state is what will be returned from view calls.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonPauseState {
+    pub mint: bool,
+    pub transfer: bool,
+    pub burn: bool,
+    pub withdraw: bool,
+    pub create_series: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Pause or resume a feature of the contract: minting (1), transfers (2), burning (3),
+    /// withdrawals (4) or series creation (5). Only the contract owner and pausers can toggle them.
+    pub fn set_paused(&mut self, feature: u8, paused: bool) {
+        self.assert_role(Role::PAUSER);
+
+        let feature = PauseFeature::from(feature);
+        if paused {
+            self.paused |= feature.flag();
+        } else {
+            self.paused &= !feature.flag();
+        }
+
+        let pause_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event: EventLogVariant::ContractPauseUpdate(vec![ContractPauseLog {
+                feature: feature.to_code(),
+                paused,
+            }]),
+        };
+        env::log_str(&pause_log.to_string());
+    }
+
+    /// Whether a feature of the contract is currently paused
+    pub fn is_paused(&self, feature: u8) -> bool {
+        self.internal_is_paused(PauseFeature::from(feature))
+    }
+
+    /// Get which features of the contract are currently paused
+    pub fn get_pause_state(&self) -> JsonPauseState {
+        JsonPauseState {
+            mint: self.internal_is_paused(PauseFeature::MINT),
+            transfer: self.internal_is_paused(PauseFeature::TRANSFER),
+            burn: self.internal_is_paused(PauseFeature::BURN),
+            withdraw: self.internal_is_paused(PauseFeature::WITHDRAW),
+            create_series: self.internal_is_paused(PauseFeature::CREATE_SERIES),
+        }
+    }
+}
+
+impl Contract {
+    /// Ensure the feature isn't paused
+    pub(crate) fn assert_not_paused(&self, feature: PauseFeature) {
+        require!(!self.internal_is_paused(feature), feature.paused_message());
+    }
+
+    pub(crate) fn internal_is_paused(&self, feature: PauseFeature) -> bool {
+        self.paused & feature.flag() != 0
+    }
+}
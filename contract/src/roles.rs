@@ -0,0 +1,168 @@
+use crate::*;
+
+// Roles that can be granted to accounts. The contract owner can act as any role.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq)]
+pub enum Role {
+    ADMIN = 1,     //1
+    CREATOR = 2,   //2
+    MINTER = 3,    //3
+    PAUSER = 4,    //4
+    MODERATOR = 5, //5
+}
+
+/// Every role, in code order
+pub const ROLES: [Role; 5] = [
+    Role::ADMIN,
+    Role::CREATOR,
+    Role::MINTER,
+    Role::PAUSER,
+    Role::MODERATOR,
+];
+
+impl Role {
+    pub fn to_code(&self) -> u8 {
+        match self {
+            Role::ADMIN => 1,
+            Role::CREATOR => 2,
+            Role::MINTER => 3,
+            Role::PAUSER => 4,
+            Role::MODERATOR => 5,
+        }
+    }
+
+    pub fn from(val: u8) -> Role {
+        match val {
+            1 => Role::ADMIN,
+            2 => Role::CREATOR,
+            3 => Role::MINTER,
+            4 => Role::PAUSER,
+            5 => Role::MODERATOR,
+            _ => panic!("Invalid Role"),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Grant a role to an account: admin (1), creator (2), minter (3), pauser (4) or moderator (5).
+    /// Admins are managed by the contract owner, every other role by the contract owner or an admin.
+    pub fn grant_role(&mut self, role: u8, account_id: AccountId) {
+        let role = Role::from(role);
+        self.assert_role_manager(role);
+        self.internal_grant_role(role, &account_id);
+    }
+
+    /// Revoke a role from an account. Same permissions as `grant_role`.
+    pub fn revoke_role(&mut self, role: u8, account_id: AccountId) {
+        let role = Role::from(role);
+        self.assert_role_manager(role);
+        self.internal_revoke_role(role, &account_id);
+    }
+
+    /// Check if an account was granted a role
+    pub fn has_role(&self, role: u8, account_id: AccountId) -> bool {
+        self.internal_has_role(Role::from(role), &account_id)
+    }
+
+    /// Paginate through the accounts a role was granted to
+    pub fn get_role_members(
+        &self,
+        role: u8,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<AccountId> {
+        let members = match self.role_members.get(&Role::from(role).to_code()) {
+            Some(members) => members,
+            None => return vec![],
+        };
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        members
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    /// Get the codes of the roles an account was granted
+    pub fn get_account_roles(&self, account_id: AccountId) -> Vec<u8> {
+        ROLES
+            .iter()
+            .filter(|role| self.internal_has_role(**role, &account_id))
+            .map(|role| role.to_code())
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Ensure that the caller is the contract owner or was granted the role
+    pub(crate) fn assert_role(&self, role: Role) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.owner_id || self.internal_has_role(role, &caller),
+            "Unauthorized: missing role"
+        );
+    }
+
+    //admins are managed by the contract owner, the other roles by admins too
+    fn assert_role_manager(&self, role: Role) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.owner_id
+                || (role != Role::ADMIN && self.internal_has_role(Role::ADMIN, &caller)),
+            "Unauthorized: only the contract owner or an admin can manage roles"
+        );
+    }
+
+    pub(crate) fn internal_has_role(&self, role: Role, account_id: &AccountId) -> bool {
+        match self.role_members.get(&role.to_code()) {
+            Some(members) => members.contains(account_id),
+            None => false,
+        }
+    }
+
+    pub(crate) fn internal_grant_role(&mut self, role: Role, account_id: &AccountId) {
+        let mut members = self.role_members.get(&role.to_code()).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::RoleMembersInner {
+                role: role.to_code(),
+            })
+        });
+        if !members.insert(account_id) {
+            return;
+        }
+        self.role_members.insert(&role.to_code(), &members);
+
+        self.log_role_event(EventLogVariant::RoleGranted(vec![RoleLog {
+            role: role.to_code(),
+            account_id: account_id.to_string(),
+        }]));
+    }
+
+    pub(crate) fn internal_revoke_role(&mut self, role: Role, account_id: &AccountId) {
+        let mut members = match self.role_members.get(&role.to_code()) {
+            Some(members) => members,
+            None => return,
+        };
+        if !members.remove(account_id) {
+            return;
+        }
+        self.role_members.insert(&role.to_code(), &members);
+
+        self.log_role_event(EventLogVariant::RoleRevoked(vec![RoleLog {
+            role: role.to_code(),
+            account_id: account_id.to_string(),
+        }]));
+    }
+
+    fn log_role_event(&self, event: EventLogVariant) {
+        let role_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event,
+        };
+        env::log_str(&role_log.to_string());
+    }
+}
@@ -3,145 +3,682 @@ use near_sdk::json_types::U64;
 
 use crate::{nft_core::NonFungibleTokenCore, *};
 
+/// What happened to a single receiver of an airdrop
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonAirdropResult {
+    pub receiver_id: AccountId,
+    // Tokens minted to the receiver, empty if it was skipped
+    pub token_ids: Vec<TokenId>,
+    // Why the receiver was skipped
+    pub error: Option<String>,
+}
+
 #[near_bindgen]
 impl Contract {
     /// Create a new series. The caller must be an approved creator. All tokens in the series will inherit the same metadata
     /// If copies are set in the metadata, it will enforce that only that number of NFTs can be minted. If not, unlimited NFTs can be minted.
     /// If a title is set in the metadata, enumeration methods will return the `${title} - ${edition}` else, `${series_id} - ${edition}`
     /// All token IDs internally are stored as `${series_id}:${edition}`
-    #[private]
+    /// A series can be created as a draft (status 1) to stage it before launch, by default it is active (status 2).
+    /// Tokens of a soulbound series cannot be transferred or approved, only burned or recovered by the contract owner.
+    /// Returns the ID of the new series. IDs are never reused.
+    /// The storage is paid by the creator, any attached $NEAR is used first and the rest comes out of its storage balance.
+    #[payable]
     pub fn create_series(
         &mut self,
         series_type: u8,
         metadata: TokenMetadata,
         royalty: Option<HashMap<AccountId, u32>>,
         price: Option<U128>,
-    ) {
+        status: Option<u8>,
+        soulbound: Option<bool>,
+    ) -> SeriesId {
+        self.assert_not_paused(PauseFeature::CREATE_SERIES);
         // Ensure the caller is an approved creator
         let caller = env::predecessor_account_id();
         require!(
-            self.approved_creators.contains(&caller) == true,
+            caller == self.owner_id || self.internal_has_role(Role::CREATOR, &caller),
             "only approved creators can add a new badge collection"
         );
         require!(series_type <= 2, "Invalid badge type");
-        let new_series_id = self.series_by_id.len() + 1;
+        let status = SeriesStatus::from(status.unwrap_or(SeriesStatus::ACTIVE.to_code()));
+        require!(
+            status == SeriesStatus::DRAFT || status == SeriesStatus::ACTIVE,
+            "A series can only be created as draft or active"
+        );
+        let initial_storage_usage = env::storage_usage();
+        let new_series_id = self.next_series_id;
+        self.next_series_id += 1;
+
+        let mut series = Series::new(
+            new_series_id,
+            metadata,
+            royalty,
+            UnorderedSet::new(StorageKey::SeriesByIdInner {
+                // We get a new unique prefix for the collection
+                account_id_hash: hash_account_id(&format!("{}{}", new_series_id, caller)),
+            }),
+            price.map(|p| p.into()),
+            caller.clone(),
+            SeriesType::from(series_type),
+        );
+        series.status = status;
+        series.soulbound = soulbound.unwrap_or(false);
+
         // Insert the series and ensure it doesn't already exist
         require!(
-            self.series_by_id
-                .insert(
-                    &new_series_id,
-                    &Series {
-                        metadata,
-                        royalty,
-                        tokens: UnorderedSet::new(StorageKey::SeriesByIdInner {
-                            // We get a new unique prefix for the collection
-                            account_id_hash: hash_account_id(&format!(
-                                "{}{}",
-                                new_series_id, caller
-                            )),
-                        }),
-                        owner_id: caller,
-                        price: price.map(|p| p.into()),
-                        series_type: SeriesType::from(series_type)
-                    }
-                )
-                .is_none(),
+            self.series_by_id.insert(&new_series_id, &series).is_none(),
             "collection ID already exists"
         );
+
+        self.internal_charge_storage(&caller, env::storage_usage() - initial_storage_usage);
+
+        new_series_id
     }
 
+    /// Update the media of a series. Only the series owner, the contract owner or a moderator can update it.
     pub fn update_badge_collection_media(
         &mut self,
         series_id: U64,
         media: Option<String>,
         media_hash: Option<Base64VecU8>,
     ) {
-        // Ensure the caller is an approved creator
-        let caller = env::predecessor_account_id();
-        require!(
-            self.approved_creators.contains(&caller) == true,
-            "only approved creators can update metadata"
-        );
-
-        // Get the series
+        // Get the series and ensure the caller owns or moderates it
         let mut series = self.series_by_id.get(&series_id.0).expect("Not a series");
+        self.assert_series_moderator(&series);
+
         series.metadata.media = media;
         series.metadata.media_hash = media_hash;
 
         self.series_by_id.insert(&series_id.0, &series);
+        self.internal_log_series_metadata_update(series_id.0, &series);
     }
 
-    /// NFT Mint for implicit accounts
-    /// The series ID must exist and if the metadata specifies a copy limit, you cannot exceed it.
-    pub fn mint_badge(&mut self, series_id: u64, receiver_id: AccountId, signature: Vec<u8>) {
-        let current_receiver_nonce = self.get_nonce(&receiver_id);
+    /// Replace the metadata and royalty of a series. Only the series owner or the contract owner can update it.
+    /// `copies` cannot be set below the number of tokens that have already been minted.
+    pub fn update_series_metadata(
+        &mut self,
+        series_id: u64,
+        metadata: TokenMetadata,
+        royalty: Option<HashMap<AccountId, u32>>,
+    ) {
+        // Get the series and ensure the caller owns it
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
 
-        // verify that `nonce` was signed by owner.
-        //thereby granting `receiver_id` permission to mint
-        let public_key = ed25519_dalek::PublicKey::from_bytes(
-            &bs58::decode(self.owner_public_key.clone())
-                .into_vec()
-                .unwrap(),
-        )
-        .unwrap();
+        if let Some(copies) = metadata.copies {
+            require!(
+                copies >= series.minted,
+                "copies cannot be lower than the number of tokens already minted"
+            );
+        }
 
-        let signature = ed25519_dalek::Signature::try_from(signature.as_ref())
-            .expect("Signature should be a valid array of 64 bytes [13, 254, 123, ...]");
+        series.metadata = metadata;
+        series.royalty = royalty;
 
-        let receiver_next_nonce = current_receiver_nonce + 1;
-        let next_nonce_hash = env::sha256(format!("{}", receiver_next_nonce).as_bytes());
+        self.series_by_id.insert(&series_id, &series);
+        self.internal_log_series_metadata_update(series_id, &series);
+    }
 
-        if let Ok(_) = public_key.verify(&next_nonce_hash, &signature) {
-            let initial_storage_usage = env::storage_usage();
+    /// Propose a new owner for a series. The ownership only changes once the proposed account
+    /// calls `accept_series_owner`. Only the series owner or the contract owner can propose.
+    pub fn propose_series_owner(&mut self, series_id: u64, new_owner_id: AccountId) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+        require!(
+            new_owner_id != series.owner_id,
+            "the proposed owner already owns the series"
+        );
 
-            self.mint_helper(series_id, receiver_id.clone());
+        series.pending_owner_id = Some(new_owner_id.clone());
+        self.series_by_id.insert(&series_id, &series);
 
-            let current_storage = env::storage_usage();
-            let storage_used = current_storage - initial_storage_usage;
-            let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
-            let required_cost = format!("{}", required_cost);
-            env::log_str(&required_cost);
+        let series_owner_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SeriesOwnerProposed(vec![SeriesOwnerProposalLog {
+                series_id,
+                owner_id: series.owner_id.to_string(),
+                proposed_owner_id: new_owner_id.to_string(),
+            }]),
+        };
+        env::log_str(&series_owner_log.to_string());
+    }
 
-            self.nonces.insert(&receiver_id, &receiver_next_nonce);
-        } else {
-            panic!("Unauthorized");
+    /// Accept the ownership of a series. Must be called by the proposed owner.
+    pub fn accept_series_owner(&mut self, series_id: u64) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        let caller = env::predecessor_account_id();
+        require!(
+            series.pending_owner_id.as_ref() == Some(&caller),
+            "only the proposed owner can accept the series"
+        );
+
+        let old_owner_id = std::mem::replace(&mut series.owner_id, caller);
+        series.pending_owner_id = None;
+        self.series_by_id.insert(&series_id, &series);
+
+        let series_owner_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SeriesOwnerTransferred(vec![SeriesOwnerTransferLog {
+                series_id,
+                old_owner_id: old_owner_id.to_string(),
+                new_owner_id: series.owner_id.to_string(),
+            }]),
+        };
+        env::log_str(&series_owner_log.to_string());
+    }
+
+    /// Cancel a pending ownership proposal. Only the series owner or the contract owner can cancel.
+    pub fn cancel_series_owner_proposal(&mut self, series_id: u64) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+
+        let proposed_owner_id = series
+            .pending_owner_id
+            .take()
+            .expect("No pending owner for the series");
+        self.series_by_id.insert(&series_id, &series);
+
+        let series_owner_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SeriesOwnerProposalCancelled(vec![SeriesOwnerProposalLog {
+                series_id,
+                owner_id: series.owner_id.to_string(),
+                proposed_owner_id: proposed_owner_id.to_string(),
+            }]),
+        };
+        env::log_str(&series_owner_log.to_string());
+    }
+
+    /// Move a series through its lifecycle: draft (1) -> active (2) <-> paused (3) -> closed (4).
+    /// Only the series owner, the contract owner or a moderator can change the status. Closing a series is permanent.
+    pub fn set_series_status(&mut self, series_id: u64, status: u8) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_moderator(&series);
+
+        let status = SeriesStatus::from(status);
+        require!(
+            series.status.can_transition_to(&status),
+            format!(
+                "Cannot change series status from {} to {}",
+                series.status.to_code(),
+                status.to_code()
+            )
+        );
+
+        let old_status = std::mem::replace(&mut series.status, status);
+        self.series_by_id.insert(&series_id, &series);
+
+        let series_status_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SeriesStatusUpdate(vec![SeriesStatusLog {
+                series_id,
+                old_status: old_status.to_code(),
+                new_status: series.status.to_code(),
+            }]),
+        };
+        env::log_str(&series_status_log.to_string());
+    }
+
+    /// Restrict minting to a time window, Unix epoch in milliseconds. Either bound can be left open with `None`.
+    /// This only controls when tokens can be minted, the tokens' own validity comes from `starts_at`/`expires_at` in the metadata.
+    pub fn set_series_mint_window(
+        &mut self,
+        series_id: u64,
+        starts_at: Option<u64>,
+        ends_at: Option<u64>,
+    ) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+        if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+            require!(starts_at < ends_at, "Mint window must start before it ends");
+        }
+
+        series.mint_starts_at = starts_at;
+        series.mint_ends_at = ends_at;
+        self.series_by_id.insert(&series_id, &series);
+    }
+
+    /// Limit how many tokens of the series can be minted to a single account, `None` removes the limit.
+    /// Setting it to 1 makes the series unique: every account can hold at most one token of it.
+    /// The limit counts tokens minted to the account, so transferring tokens away doesn't free up allowance.
+    pub fn set_series_max_per_account(&mut self, series_id: u64, max_per_account: Option<u64>) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+
+        series.max_per_account = max_per_account;
+        self.series_by_id.insert(&series_id, &series);
+    }
+
+    /// Sponsor the storage of the tokens minted from the series. While sponsored, it is paid by the series owner's
+    /// storage balance instead of the minter's or the receiver's.
+    pub fn set_series_storage_sponsorship(&mut self, series_id: u64, sponsored: bool) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+
+        series.storage_sponsored = sponsored;
+        self.series_by_id.insert(&series_id, &series);
+    }
+
+    /// Set the merkle root of the accounts that can mint through `mint_with_proof`, `None` disables the allowlist.
+    /// Leaves are `sha256(account_id)` for accounts allowed a single token, or `sha256("{account_id}:{quantity}")`
+    /// for accounts allowed `quantity` tokens. Nodes are hashed as `sha256(min(a, b) + max(a, b))`.
+    pub fn set_series_allowlist(&mut self, series_id: u64, root: Option<Base64VecU8>) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+
+        series.allowlist_root = root.map(|root| {
+            root.0
+                .try_into()
+                .expect("Allowlist root should be a 32 bytes sha256 hash")
+        });
+        self.series_by_id.insert(&series_id, &series);
+    }
+
+    /// Mint `amount` tokens of a series to the caller, proving it is on the series allowlist.
+    /// `quantity` must be passed if the caller's leaf was built with a per account quantity.
+    /// Returns the IDs of the minted tokens.
+    /// The storage is paid by the caller's storage balance, or the series owner's if it sponsors the series.
+    pub fn mint_with_proof(
+        &mut self,
+        series_id: u64,
+        proof: Vec<Base64VecU8>,
+        amount: u32,
+        quantity: Option<u64>,
+    ) -> Vec<TokenId> {
+        require!(amount > 0, "Amount must be greater than 0");
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        let root = series.allowlist_root.expect("Series has no allowlist");
+        let receiver_id = env::predecessor_account_id();
+
+        // rebuild the caller's leaf and check it is part of the tree
+        let leaf = match quantity {
+            Some(quantity) => env::sha256(format!("{}:{}", receiver_id, quantity).as_bytes()),
+            None => env::sha256(receiver_id.as_bytes()),
+        };
+        require!(
+            verify_merkle_proof(leaf, &proof, &root),
+            "Unauthorized: invalid allowlist proof"
+        );
+
+        // ensure the caller doesn't claim more than its allowance
+        let claimed = series.allowlist_claimed.get(&receiver_id).unwrap_or(0);
+        let allowance = quantity.unwrap_or(1);
+        require!(
+            claimed + u64::from(amount) <= allowance,
+            format!(
+                "Allowlist allowance exceeded, {} of {} already claimed",
+                claimed, allowance
+            )
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        series
+            .allowlist_claimed
+            .insert(&receiver_id, &(claimed + u64::from(amount)));
+
+        let token_ids = self.mint_tokens(series_id, receiver_id.clone(), amount, None);
+        self.internal_charge_mint_storage(
+            series_id,
+            &receiver_id,
+            &token_ids,
+            initial_storage_usage,
+        );
+
+        token_ids
+    }
+
+    /// Register one-time claim codes for a series. Only the sha256 hash of each code is stored,
+    /// the codes themselves are handed out off-chain (e.g. as QR codes) and redeemed with `claim_with_code`.
+    /// The storage is paid by the attached deposit or the series owner's storage balance.
+    #[payable]
+    pub fn add_claim_codes(&mut self, series_id: u64, code_hashes: Vec<Base64VecU8>) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+        let initial_storage_usage = env::storage_usage();
+
+        for code_hash in code_hashes {
+            let code_hash: CryptoHash = code_hash
+                .0
+                .try_into()
+                .expect("Claim code hash should be a 32 bytes sha256 hash");
+            require!(
+                !series.used_claim_codes.contains(&code_hash),
+                "Claim code was already used"
+            );
+            series.claim_codes.insert(&code_hash);
+        }
+        self.series_by_id.insert(&series_id, &series);
+
+        self.internal_charge_storage(
+            &series.owner_id,
+            env::storage_usage().saturating_sub(initial_storage_usage),
+        );
+    }
+
+    /// Revoke claim codes that haven't been redeemed yet. Returns how many codes were revoked.
+    pub fn revoke_claim_codes(&mut self, series_id: u64, code_hashes: Vec<Base64VecU8>) -> u64 {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+
+        let mut revoked = 0;
+        for code_hash in code_hashes {
+            if let Ok(code_hash) = CryptoHash::try_from(code_hash.0) {
+                if series.claim_codes.remove(&code_hash) {
+                    revoked += 1;
+                }
+            }
+        }
+        self.series_by_id.insert(&series_id, &series);
+        revoked
+    }
+
+    /// Redeem a claim code, minting a token of the series to the receiver.
+    /// Each code can only be redeemed once. Returns the ID of the minted token.
+    /// The storage is paid by the receiver's storage balance, or the series owner's if it sponsors the series.
+    pub fn claim_with_code(
+        &mut self,
+        series_id: u64,
+        code: String,
+        receiver_id: AccountId,
+    ) -> TokenId {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        let initial_storage_usage = env::storage_usage();
+
+        // the code is valid if its hash was registered and it hasn't been redeemed yet
+        let code_hash: CryptoHash = env::sha256(code.as_bytes()).try_into().unwrap();
+        require!(
+            series.claim_codes.remove(&code_hash),
+            "Invalid or already used claim code"
+        );
+        series.used_claim_codes.insert(&code_hash);
+        self.series_by_id.insert(&series_id, &series);
+
+        let token_id = self.mint_helper(series_id, receiver_id.clone(), None);
+        self.internal_charge_mint_storage(
+            series_id,
+            &receiver_id,
+            std::slice::from_ref(&token_id),
+            initial_storage_usage,
+        );
+
+        token_id
+    }
+
+    /// Register an ed25519 public key (base58) that can sign mint vouchers for this series only.
+    /// Lets series owners mint through their own backend without the contract owner's key.
+    /// The storage is paid by the attached deposit or the series owner's storage balance.
+    #[payable]
+    pub fn add_series_minting_key(&mut self, series_id: u64, public_key: String) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+        let initial_storage_usage = env::storage_usage();
+
+        // make sure the key can be used before storing it
+        parse_public_key(&public_key);
+        require!(
+            series.minting_keys.insert(&public_key),
+            "Minting key is already registered for the series"
+        );
+        self.series_by_id.insert(&series_id, &series);
+        self.internal_charge_storage(
+            &series.owner_id,
+            env::storage_usage().saturating_sub(initial_storage_usage),
+        );
+
+        let minting_key_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SeriesMintingKeyAdded(vec![SeriesMintingKeyLog {
+                series_id,
+                public_key,
+            }]),
+        };
+        env::log_str(&minting_key_log.to_string());
+    }
+
+    /// Remove a minting key from a series, vouchers signed with it can no longer be redeemed.
+    pub fn remove_series_minting_key(&mut self, series_id: u64, public_key: String) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+
+        require!(
+            series.minting_keys.remove(&public_key),
+            "Minting key is not registered for the series"
+        );
+        self.series_by_id.insert(&series_id, &series);
+
+        let minting_key_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SeriesMintingKeyRemoved(vec![SeriesMintingKeyLog {
+                series_id,
+                public_key,
+            }]),
+        };
+        env::log_str(&minting_key_log.to_string());
+    }
+
+    /// Allow an account to mint this series directly through `nft_mint`, without vouchers.
+    /// The storage is paid by the attached deposit or the series owner's storage balance.
+    #[payable]
+    pub fn add_series_minter(&mut self, series_id: u64, account_id: AccountId) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+        let initial_storage_usage = env::storage_usage();
+
+        series.minters.insert(&account_id);
+        self.series_by_id.insert(&series_id, &series);
+
+        self.internal_charge_storage(
+            &series.owner_id,
+            env::storage_usage().saturating_sub(initial_storage_usage),
+        );
+    }
+
+    /// Revoke an account's permission to mint this series directly.
+    pub fn remove_series_minter(&mut self, series_id: u64, account_id: AccountId) {
+        let mut series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_owner(&series);
+
+        series.minters.remove(&account_id);
+        self.series_by_id.insert(&series_id, &series);
+    }
+
+    /// Mint `amount` tokens of a series to the receiver without a voucher.
+    /// Only approved minters, or minters added to the series by its owner, can call it.
+    /// The storage is paid by the caller, or the series owner if it sponsors the series.
+    /// Any attached $NEAR is used first and the excess refunded, the rest comes out of the payer's storage balance.
+    #[payable]
+    pub fn nft_mint(
+        &mut self,
+        series_id: u64,
+        receiver_id: AccountId,
+        amount: u32,
+        memo: Option<String>,
+    ) -> Vec<TokenId> {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_minter(&series);
+        require!(amount > 0, "Amount must be greater than 0");
+
+        let initial_storage_usage = env::storage_usage();
+
+        let token_ids = self.mint_tokens(series_id, receiver_id, amount, memo);
+
+        //charge the storage and refund the excess
+        let payer_id = series.storage_payer(&env::predecessor_account_id());
+        self.internal_record_storage_payer(&token_ids, &payer_id);
+        self.internal_charge_storage(&payer_id, env::storage_usage() - initial_storage_usage);
+
+        token_ids
+    }
+
+    /// Mint a series to many receivers at once, e.g. to airdrop a badge. Each entry is a receiver and how many tokens it gets.
+    /// Receivers that can't get their tokens, e.g. because they reached the per account limit, are skipped and
+    /// reported in the result instead of failing the whole call.
+    /// Only approved minters, or minters added to the series by its owner, can call it.
+    /// The storage is paid by the caller, or the series owner if it sponsors the series.
+    /// Any attached $NEAR is used first and the excess refunded, the rest comes out of the payer's storage balance.
+    #[payable]
+    pub fn nft_airdrop(
+        &mut self,
+        series_id: u64,
+        receivers: Vec<(AccountId, u32)>,
+        memo: Option<String>,
+    ) -> Vec<JsonAirdropResult> {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        self.assert_series_minter(&series);
+        // nothing can be minted to anyone if the series itself is blocked
+        if let Some(reason) = series.mint_blocker() {
+            panic!("{}", reason);
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        let mut results = Vec::with_capacity(receivers.len());
+        let mut mint_logs: Vec<NftMintLog> = vec![];
+        //position of each owner in the mint logs, so receivers listed twice share an entry
+        let mut mint_log_index: HashMap<AccountId, usize> = HashMap::new();
+        for (receiver_id, amount) in receivers {
+            if let Some(error) = self.airdrop_blocker(series_id, &receiver_id, amount) {
+                results.push(JsonAirdropResult {
+                    receiver_id,
+                    token_ids: vec![],
+                    error: Some(error),
+                });
+                continue;
+            }
+
+            let token_ids: Vec<TokenId> = (0..amount)
+                .map(|_| self.internal_mint(series_id, receiver_id.clone()))
+                .collect();
+            match mint_log_index.get(&receiver_id) {
+                Some(index) => mint_logs[*index].token_ids.extend(token_ids.clone()),
+                None => {
+                    mint_log_index.insert(receiver_id.clone(), mint_logs.len());
+                    mint_logs.push(NftMintLog {
+                        owner_id: receiver_id.to_string(),
+                        token_ids: token_ids.clone(),
+                        memo: memo.clone(),
+                    });
+                }
+            }
+            results.push(JsonAirdropResult {
+                receiver_id,
+                token_ids,
+                error: None,
+            });
         }
+        log_nft_mints(mint_logs);
+
+        //charge the storage and refund the excess
+        let payer_id = series.storage_payer(&env::predecessor_account_id());
+        for result in results.iter() {
+            self.internal_record_storage_payer(&result.token_ids, &payer_id);
+        }
+        self.internal_charge_storage(&payer_id, env::storage_usage() - initial_storage_usage);
+
+        results
+    }
+
+    /// NFT Mint for implicit accounts
+    /// The series ID must exist and if the metadata specifies a copy limit, you cannot exceed it.
+    /// The voucher must be issued for `mint_badge`, this series and receiver with an amount of 1, and signed by the owner.
+    /// The storage is paid by the receiver's storage balance, or the series owner's if it sponsors the series.
+    pub fn mint_badge(
+        &mut self,
+        series_id: u64,
+        receiver_id: AccountId,
+        voucher: MintVoucher,
+        signature: Vec<u8>,
+    ) {
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_use_mint_voucher(
+            &voucher,
+            &signature,
+            "mint_badge",
+            series_id,
+            &receiver_id,
+            1,
+        );
+
+        let token_id = self.mint_helper(series_id, receiver_id.clone(), None);
+
+        self.internal_charge_mint_storage(
+            series_id,
+            &receiver_id,
+            &[token_id],
+            initial_storage_usage,
+        );
     }
 
+    /// Mint `amount` tokens of a series to the receiver.
+    /// The voucher must be issued for `batch_mint`, this series, receiver and amount, and signed by the owner.
+    /// Mints as many tokens as the attached gas allows. If some are left, returns a job ID to finish with `continue_job`.
+    /// The storage is paid by the receiver's storage balance, or the series owner's if it sponsors the series.
     pub fn batch_mint(
         &mut self,
         series_id: u64,
         amount: u8,
         receiver_id: AccountId,
+        voucher: MintVoucher,
         signature: Vec<u8>,
-    ) {
-        let current_receiver_nonce = self.get_nonce(&receiver_id);
+        memo: Option<String>,
+    ) -> Option<u64> {
+        let initial_storage_usage = env::storage_usage();
+        self.internal_use_mint_voucher(
+            &voucher,
+            &signature,
+            "batch_mint",
+            series_id,
+            &receiver_id,
+            amount.into(),
+        );
+        //the job charges the storage of the tokens, the voucher's nonce is charged here
+        self.internal_charge_mint_storage(series_id, &receiver_id, &[], initial_storage_usage);
 
-        // verify that `nonce` was signed by owner.
-        //thereby granting `receiver_id` permission to mint
-        let public_key = ed25519_dalek::PublicKey::from_bytes(
-            &bs58::decode(self.owner_public_key.clone())
-                .into_vec()
-                .unwrap(),
-        )
-        .unwrap();
+        self.internal_start_job(Job {
+            kind: JobKind::MINT,
+            series_id,
+            owner_id: receiver_id,
+            receiver_id: None,
+            processed: 0,
+            remaining: amount.into(),
+            memo,
+        })
+    }
 
-        let signature = ed25519_dalek::Signature::try_from(signature.as_ref())
-            .expect("Signature should be a valid array of 64 bytes [13, 254, 123, ...]");
+    /// Buy a token from a series that has a price set, minting it to `receiver_id`.
+    /// The caller must attach the series price plus the cost of the storage the token takes up.
+    /// The price is sent to the series owner and anything attached above the total is refunded.
+    #[payable]
+    pub fn nft_mint_paid(&mut self, series_id: u64, receiver_id: AccountId) -> TokenId {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        let price = series.price.expect("Series is not for sale");
 
-        let receiver_next_nonce = current_receiver_nonce + 1;
-        let next_nonce_hash = env::sha256(format!("{}", receiver_next_nonce).as_bytes());
+        let initial_storage_usage = env::storage_usage();
 
-        if let Ok(_) = public_key.verify(&next_nonce_hash, &signature) {
-            for _i in 0..amount {
-                self.mint_helper(series_id, receiver_id.clone());
-            }
-            self.nonces.insert(&receiver_id, &receiver_next_nonce);
-        } else {
-            panic!("Unauthorized: Invalid signature");
-        }
+        let token_id = self.mint_helper(series_id, receiver_id, None);
+        self.internal_record_storage_payer(
+            std::slice::from_ref(&token_id),
+            &env::predecessor_account_id(),
+        );
+
+        //charge the price and storage, pay the series owner and refund the excess
+        let storage_used = env::storage_usage() - initial_storage_usage;
+        payout_series_owner(storage_used, price, series.owner_id);
+
+        token_id
     }
 
+    /// Withdraw `amount` tokens of a series from the implicit account of `owner_public_key` to the receiver.
+    /// Withdraws as many tokens as the attached gas allows. If some are left, returns a job ID to finish with `continue_job`.
     pub fn batch_withdraw(
         &mut self,
         series_id: u64,
@@ -149,41 +686,40 @@ impl Contract {
         owner_public_key: String,
         receiver_id: AccountId,
         signature: Vec<u8>,
-    ) {
+        nonce: Option<u64>,
+    ) -> Option<u64> {
+        self.assert_not_paused(PauseFeature::WITHDRAW);
         //fetch
         let public_key = ed25519_dalek::PublicKey::from_bytes(
             &bs58::decode(owner_public_key).into_vec().unwrap(),
         )
         .unwrap();
-        let signer_account_id = AccountId::new_unchecked(hex::encode(public_key));
-        let tokens = self.owner_nft_tokens_for_series(
-            series_id,
-            signer_account_id.clone(),
-            Some(U128(0)),
-            Some(amount),
-        );
-        let msg = format!("owner_id: {}", signer_account_id.clone());
-        env::log_str(&msg);
         //actual token owner
-        let owner_id = tokens[0].owner_id.clone();
+        let owner_id = AccountId::new_unchecked(hex::encode(public_key));
+        let msg = format!("owner_id: {}", owner_id.clone());
+        env::log_str(&msg);
 
-        let current_owner_nonce = self.get_nonce(&owner_id);
-        let owner_next_nonce = current_owner_nonce + 1;
-        let next_nonce_hash = env::sha256(format!("{}", owner_next_nonce).as_bytes());
+        //any unused nonce can be signed, defaults to the one after the highest used
+        let owner_nonce = nonce.unwrap_or_else(|| self.get_nonce(&owner_id) + 1);
+        let nonce_hash = env::sha256(format!("{}", owner_nonce).as_bytes());
         let signature = ed25519_dalek::Signature::try_from(signature.as_ref())
             .expect("Signature should be a valid array of 64 bytes [13, 254, 123, ...]");
 
         // owner pubkey must be the signer of the transaction
-        if let Ok(_) = public_key.verify(&next_nonce_hash, &signature) {
-            //
-            for token in tokens {
-                self.internal_transfer(&owner_id, &receiver_id, &token.token_id, None, None);
-            }
-
-            self.nonces.insert(&owner_id, &owner_next_nonce);
-        } else {
+        if public_key.verify(&nonce_hash, &signature).is_err() {
             panic!("Unauthorized: invalid signature");
         }
+        self.internal_use_nonce(&owner_id, NonceScope::OWNER, owner_nonce);
+
+        self.internal_start_job(Job {
+            kind: JobKind::WITHDRAW,
+            series_id,
+            owner_id,
+            receiver_id: Some(receiver_id),
+            processed: 0,
+            remaining: amount,
+            memo: None,
+        })
     }
     //transfer to external wallet
     pub fn withdraw(
@@ -192,7 +728,9 @@ impl Contract {
         receiver_id: AccountId,
         token_id: TokenId,
         signature: Vec<u8>,
+        nonce: Option<u64>,
     ) {
+        self.assert_not_paused(PauseFeature::WITHDRAW);
         let public_key = ed25519_dalek::PublicKey::from_bytes(
             &bs58::decode(owner_public_key).into_vec().unwrap(),
         )
@@ -206,38 +744,133 @@ impl Contract {
             signer_account_id == owner_id,
             "Unauthorized: Not token owner"
         );
-        let current_owner_nonce = self.get_nonce(&owner_id);
-        let owner_next_nonce = current_owner_nonce + 1;
-        let next_nonce_hash = env::sha256(format!("{}", owner_next_nonce).as_bytes());
+        //any unused nonce can be signed, defaults to the one after the highest used
+        let owner_nonce = nonce.unwrap_or_else(|| self.get_nonce(&owner_id) + 1);
+        let nonce_hash = env::sha256(format!("{}", owner_nonce).as_bytes());
         let signature = ed25519_dalek::Signature::try_from(signature.as_ref())
             .expect("Signature should be a valid array of 64 bytes [13, 254, 123, ...]");
 
         // owner pubkey must be the signer of the transaction
-        if let Ok(_) = public_key.verify(&next_nonce_hash, &signature) {
+        if let Ok(_) = public_key.verify(&nonce_hash, &signature) {
             self.internal_transfer(&owner_id, &receiver_id, &token_id, None, None);
-            self.nonces.insert(&owner_id, &owner_next_nonce);
+            self.internal_use_nonce(&owner_id, NonceScope::OWNER, owner_nonce);
         } else {
             panic!("Unauthorized: invalid signature");
         }
     }
 
-    fn mint_helper(&mut self, series_id: u64, receiver_id: AccountId) {
+    /// Move a token to another account on behalf of its owner, e.g. when the owner lost access to their account.
+    /// This is the only way a soulbound token can change owner. Only the contract owner can recover tokens.
+    pub fn recover_token(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        memo: Option<String>,
+    ) {
+        self.assert_contract_owner();
+
+        let owner_id = self.tokens_by_id.get(&token_id).expect("No token").owner_id;
+        let previous_token =
+            self.internal_transfer_unrestricted(&owner_id, &receiver_id, &token_id, None, memo);
+
+        //we refund the owner for releasing the storage used up by the approved account IDs
+        refund_approved_account_ids(
+            previous_token.owner_id.clone(),
+            &previous_token.approved_account_ids,
+        );
+    }
+
+    fn mint_helper(
+        &mut self,
+        series_id: u64,
+        receiver_id: AccountId,
+        memo: Option<String>,
+    ) -> TokenId {
+        self.mint_tokens(series_id, receiver_id, 1, memo).remove(0)
+    }
+
+    //mint `amount` tokens of the series to the receiver, logged as a single nft_mint entry
+    fn mint_tokens(
+        &mut self,
+        series_id: u64,
+        receiver_id: AccountId,
+        amount: u32,
+        memo: Option<String>,
+    ) -> Vec<TokenId> {
+        let token_ids: Vec<TokenId> = (0..amount)
+            .map(|_| self.internal_mint(series_id, receiver_id.clone()))
+            .collect();
+
+        log_nft_mints(vec![NftMintLog {
+            // Owner of the tokens.
+            owner_id: receiver_id.to_string(),
+            // Vector of token IDs that were minted.
+            token_ids: token_ids.clone(),
+            // An optional memo to include.
+            memo,
+        }]);
+
+        token_ids
+    }
+
+    /// Charge the storage used since `initial_storage_usage` by `token_ids`, minted to the receiver
+    pub(crate) fn internal_charge_mint_storage(
+        &mut self,
+        series_id: u64,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        initial_storage_usage: u64,
+    ) {
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        let payer_id = series.storage_payer(receiver_id);
+        self.internal_record_storage_payer(token_ids, &payer_id);
+        self.internal_charge_storage(
+            &payer_id,
+            env::storage_usage().saturating_sub(initial_storage_usage),
+        );
+    }
+
+    //remember who paid for the storage of the tokens, so it gets the storage back when they are burned
+    pub(crate) fn internal_record_storage_payer(
+        &mut self,
+        token_ids: &[TokenId],
+        payer_id: &AccountId,
+    ) {
+        for token_id in token_ids {
+            self.token_storage_payers.insert(token_id, payer_id);
+        }
+    }
+
+    //mint a single token of the series, without logging it
+    pub(crate) fn internal_mint(&mut self, series_id: u64, receiver_id: AccountId) -> TokenId {
+        self.assert_not_paused(PauseFeature::MINT);
         // Get the series and how many tokens currently exist (edition number = cur_len + 1)
         let mut series = self.series_by_id.get(&series_id).expect("Not a series");
-
-        let cur_len = series.tokens.len();
-        // // Ensure we haven't overflowed on the number of copies minted
-        if let Some(copies) = series.metadata.copies.clone() {
+        // Ensure the series can currently be minted from
+        if let Some(reason) = series.mint_blocker() {
+            panic!("{}", reason);
+        }
+        // Ensure the receiver hasn't reached the per account limit
+        let minted_to_receiver = self.internal_minted_to_account(&series, &receiver_id);
+        if let Some(max_per_account) = series.max_per_account {
             require!(
-                cur_len < copies,
-                "cannot mint anymore NFTs for the given series. Limit reached"
+                minted_to_receiver < max_per_account,
+                "Mint limit per account reached for this series"
             );
         }
+        series
+            .minted_per_account
+            .insert(&receiver_id, &(minted_to_receiver + 1));
+
+        let edition = series.next_edition;
+        series.next_edition += 1;
 
         // // The token ID is stored internally as `${series_id}:${edition}`
-        let token_id = format!("{}:{}", series_id, cur_len + 1);
+        let token_id = format!("{}:{}", series_id, edition);
         series.tokens.insert(&token_id);
+        series.minted += 1;
         self.series_by_id.insert(&series_id, &series);
+        self.total_minted += 1;
 
         let token = Token {
             // Series ID that the token belongs to
@@ -259,93 +892,137 @@ impl Contract {
         //call the internal method for adding the token to the owner
         self.internal_add_token_to_owner(&token.owner_id, &token_id);
 
-        // Construct the mint log as per the events standard.
-        let nft_mint_log: EventLog = EventLog {
-            // Standard name ("nep171").
-            standard: NFT_STANDARD_NAME.to_string(),
-            // Version of the standard ("nft-1.0.0").
-            version: NFT_METADATA_SPEC.to_string(),
-            // The data related with the event stored in a vector.
-            event: EventLogVariant::NftMint(vec![NftMintLog {
-                // Owner of the token.
-                owner_id: token.owner_id.to_string(),
-                // Vector of token IDs that were minted.
-                token_ids: vec![token_id.to_string()],
-                // An optional memo to include.
-                memo: None,
-            }]),
-        };
-
-        // Log the serialized json.
-        env::log_str(&nft_mint_log.to_string());
+        token_id
     }
-    ///mint without restriction
-    /// for testing purposes only
-    #[cfg(test)]
-    pub fn badge_mint_test(&mut self, id: U64, receiver_id: AccountId) {
-        let initial_storage_usage = env::storage_usage();
 
-        // Get the series and how many tokens currently exist (edition number = cur_len + 1)
-        let mut series = self.series_by_id.get(&id.0).expect("Not a series");
+    //why the tokens can't be airdropped to the receiver, `None` if they can
+    fn airdrop_blocker(
+        &self,
+        series_id: u64,
+        receiver_id: &AccountId,
+        amount: u32,
+    ) -> Option<String> {
+        if amount == 0 {
+            return Some("Amount must be greater than 0".to_string());
+        }
 
-        let cur_len = series.tokens.len();
-        // Ensure we haven't overflowed on the number of copies minted
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        if let Some(reason) = series.mint_blocker() {
+            return Some(reason);
+        }
         if let Some(copies) = series.metadata.copies {
-            require!(
-                cur_len < copies,
-                "cannot mint anymore NFTs for the given series. Limit reached"
-            );
+            let copies_left = copies - series.minted;
+            if u64::from(amount) > copies_left {
+                return Some(format!("Only {} copies left in the series", copies_left));
+            }
+        }
+        if let Some(max_per_account) = series.max_per_account {
+            let minted = self.internal_minted_to_account(&series, receiver_id);
+            if minted + u64::from(amount) > max_per_account {
+                return Some("Mint limit per account reached for this series".to_string());
+            }
         }
 
-        // The token ID is stored internally as `${series_id}:${edition}`
-        let token_id = format!("{}:{}", id.0, cur_len + 1);
-        series.tokens.insert(&token_id);
-        self.series_by_id.insert(&id.0, &series);
-
-        //specify the token struct that contains the owner ID
-        let token = Token {
-            // Series ID that the token belongs to
-            series_id: id.0,
-            //set the owner ID equal to the receiver ID passed into the function
-            owner_id: receiver_id.clone(),
-            //we set the approved account IDs to the default value (an empty map)
-            approved_account_ids: Default::default(),
-            //the next approval ID is set to 0
-            next_approval_id: 0,
-        };
+        None
+    }
 
-        //insert the token ID and token struct and make sure that the token doesn't exist
-        require!(
-            self.tokens_by_id.insert(&token_id, &token).is_none(),
-            "Token already exists"
-        );
+    ///mint without a voucher
+    /// for testing purposes only
+    #[cfg(test)]
+    pub fn badge_mint_test(&mut self, id: U64, receiver_id: AccountId) {
+        let initial_storage_usage = env::storage_usage();
 
-        //call the internal method for adding the token to the owner
-        self.internal_add_token_to_owner(&token.owner_id, &token_id);
+        self.mint_helper(id.0, receiver_id, None);
 
-        // Construct the mint log as per the events standard.
-        let nft_mint_log: EventLog = EventLog {
-            // Standard name ("nep171").
-            standard: NFT_STANDARD_NAME.to_string(),
-            // Version of the standard ("nft-1.0.0").
-            version: NFT_METADATA_SPEC.to_string(),
-            // The data related with the event stored in a vector.
-            event: EventLogVariant::NftMint(vec![NftMintLog {
-                // Owner of the token.
-                owner_id: token.owner_id.to_string(),
-                // Vector of token IDs that were minted.
-                token_ids: vec![token_id.to_string()],
-                // An optional memo to include.
-                memo: None,
-            }]),
-        };
         let current_storage = env::storage_usage();
         let storage_used = current_storage - initial_storage_usage;
         let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
         let required_cost = format!("{}", required_cost);
 
         env::log_str(&required_cost);
-        // Log the serialized json.
-        env::log_str(&nft_mint_log.to_string());
+    }
+}
+
+impl Series {
+    /// A new active series with no tokens, restrictions or extra permissions.
+    /// `tokens` is the set the series keeps its token IDs in.
+    pub(crate) fn new(
+        series_id: SeriesId,
+        metadata: TokenMetadata,
+        royalty: Option<HashMap<AccountId, u32>>,
+        tokens: UnorderedSet<TokenId>,
+        price: Option<Balance>,
+        owner_id: AccountId,
+        series_type: SeriesType,
+    ) -> Self {
+        Series {
+            metadata,
+            royalty,
+            tokens,
+            price,
+            owner_id,
+            series_type,
+            pending_owner_id: None,
+            status: SeriesStatus::ACTIVE,
+            mint_starts_at: None,
+            mint_ends_at: None,
+            soulbound: false,
+            max_per_account: None,
+            minted_per_account: LookupMap::new(StorageKey::SeriesMintedPerAccount { series_id }),
+            allowlist_root: None,
+            allowlist_claimed: LookupMap::new(StorageKey::SeriesAllowlistClaimed { series_id }),
+            claim_codes: UnorderedSet::new(StorageKey::SeriesClaimCodes { series_id }),
+            used_claim_codes: LookupSet::new(StorageKey::SeriesUsedClaimCodes { series_id }),
+            minting_keys: UnorderedSet::new(StorageKey::SeriesMintingKeys { series_id }),
+            minters: UnorderedSet::new(StorageKey::SeriesMinters { series_id }),
+            next_edition: 1,
+            minted: 0,
+            burned: 0,
+            storage_sponsored: false,
+        }
+    }
+
+    /// Who pays for the storage of tokens minted from the series: the series owner
+    /// if it sponsors the series, `account_id` otherwise
+    pub(crate) fn storage_payer(&self, account_id: &AccountId) -> AccountId {
+        if self.storage_sponsored {
+            self.owner_id.clone()
+        } else {
+            account_id.clone()
+        }
+    }
+
+    /// Why no token can be minted from the series right now, `None` if minting is allowed
+    pub(crate) fn mint_blocker(&self) -> Option<String> {
+        match self.status {
+            SeriesStatus::ACTIVE => {}
+            SeriesStatus::DRAFT => return Some("Series is still a draft".to_string()),
+            SeriesStatus::PAUSED => return Some("Series is paused".to_string()),
+            SeriesStatus::CLOSED => return Some("Series is closed".to_string()),
+        }
+
+        // Ensure we are within the mint window
+        let now = env::block_timestamp_ms();
+        if let Some(mint_starts_at) = self.mint_starts_at {
+            if now < mint_starts_at {
+                return Some("Minting has not started for this series".to_string());
+            }
+        }
+        if let Some(mint_ends_at) = self.mint_ends_at {
+            if now >= mint_ends_at {
+                return Some("Minting has ended for this series".to_string());
+            }
+        }
+
+        // Ensure we haven't overflowed on the number of copies minted
+        if let Some(copies) = self.metadata.copies {
+            if self.minted >= copies {
+                return Some(
+                    "cannot mint anymore NFTs for the given series. Limit reached".to_string(),
+                );
+            }
+        }
+
+        None
     }
 }
@@ -0,0 +1,165 @@
+use crate::*;
+
+/// A key the owner's backend signs mint vouchers with
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SigningKey {
+    // Base58 encoded ed25519 public key
+    pub public_key: String,
+    // When the key was registered, Unix epoch in milliseconds
+    pub added_at: u64,
+    // When the key stops being accepted, Unix epoch in milliseconds
+    pub expires_at: Option<u64>,
+    // When the key was retired, retired keys are never accepted again
+    pub retired_at: Option<u64>,
+}
+
+impl SigningKey {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.retired_at.is_none()
+            && !matches!(self.expires_at, Some(expires_at) if now >= expires_at)
+    }
+}
+
+//The Json signing key is what will be returned from view calls.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonSigningKey {
+    pub key_id: String,
+    pub public_key: String,
+    pub added_at: u64,
+    pub expires_at: Option<u64>,
+    pub retired_at: Option<u64>,
+    // Whether vouchers signed with the key are currently accepted
+    pub active: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Register a new key that can sign mint vouchers. The key is validated when it is registered.
+    /// Keys can be given an expiry, Unix epoch in milliseconds, after which they are no longer accepted.
+    pub fn add_signing_key(&mut self, key_id: String, public_key: String, expires_at: Option<u64>) {
+        self.assert_contract_owner();
+        self.internal_add_signing_key(key_id, public_key, expires_at);
+    }
+
+    /// Retire a signing key, vouchers signed with it can no longer be redeemed.
+    pub fn retire_signing_key(&mut self, key_id: String) {
+        self.assert_contract_owner();
+        self.internal_retire_signing_key(key_id);
+    }
+
+    /// Schedule when a signing key stops being accepted, Unix epoch in milliseconds. `None` removes the expiry.
+    /// Useful to keep the old key valid for a grace period while rotating to a new one.
+    pub fn set_signing_key_expiry(&mut self, key_id: String, expires_at: Option<u64>) {
+        self.assert_contract_owner();
+
+        let mut signing_key = self.signing_keys.get(&key_id).expect("No signing key");
+        require!(
+            signing_key.retired_at.is_none(),
+            "Signing key is already retired"
+        );
+        signing_key.expires_at = expires_at;
+        self.signing_keys.insert(&key_id, &signing_key);
+
+        self.log_signing_key_event(EventLogVariant::SigningKeyExpiryUpdate(vec![
+            SigningKeyLog {
+                key_id,
+                public_key: signing_key.public_key,
+                expires_at,
+            },
+        ]));
+    }
+
+    /// Get all the signing keys that were ever registered
+    pub fn get_signing_keys(&self) -> Vec<JsonSigningKey> {
+        let now = env::block_timestamp_ms();
+        self.signing_keys
+            .iter()
+            .map(|(key_id, signing_key)| JsonSigningKey {
+                key_id,
+                active: signing_key.is_active(now),
+                public_key: signing_key.public_key,
+                added_at: signing_key.added_at,
+                expires_at: signing_key.expires_at,
+                retired_at: signing_key.retired_at,
+            })
+            .collect()
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_signing_key(
+        &mut self,
+        key_id: String,
+        public_key: String,
+        expires_at: Option<u64>,
+    ) {
+        // make sure the key can be used before storing it
+        parse_public_key(&public_key);
+        require!(
+            self.signing_keys.get(&key_id).is_none(),
+            "Signing key ID already exists"
+        );
+
+        self.signing_keys.insert(
+            &key_id,
+            &SigningKey {
+                public_key: public_key.clone(),
+                added_at: env::block_timestamp_ms(),
+                expires_at,
+                retired_at: None,
+            },
+        );
+
+        self.log_signing_key_event(EventLogVariant::SigningKeyAdded(vec![SigningKeyLog {
+            key_id,
+            public_key,
+            expires_at,
+        }]));
+    }
+
+    pub(crate) fn internal_retire_signing_key(&mut self, key_id: String) {
+        let mut signing_key = self.signing_keys.get(&key_id).expect("No signing key");
+        require!(
+            signing_key.retired_at.is_none(),
+            "Signing key is already retired"
+        );
+        signing_key.retired_at = Some(env::block_timestamp_ms());
+        self.signing_keys.insert(&key_id, &signing_key);
+
+        self.log_signing_key_event(EventLogVariant::SigningKeyRetired(vec![SigningKeyLog {
+            key_id,
+            public_key: signing_key.public_key,
+            expires_at: signing_key.expires_at,
+        }]));
+    }
+
+    //IDs of the signing keys that are currently accepted
+    pub(crate) fn internal_active_signing_key_ids(&self) -> Vec<String> {
+        let now = env::block_timestamp_ms();
+        self.signing_keys
+            .iter()
+            .filter(|(_, signing_key)| signing_key.is_active(now))
+            .map(|(key_id, _)| key_id)
+            .collect()
+    }
+
+    //public keys of the signing keys that are currently accepted
+    pub(crate) fn internal_active_signing_keys(&self) -> Vec<String> {
+        let now = env::block_timestamp_ms();
+        self.signing_keys
+            .values()
+            .filter(|signing_key| signing_key.is_active(now))
+            .map(|signing_key| signing_key.public_key)
+            .collect()
+    }
+
+    fn log_signing_key_event(&self, event: EventLogVariant) {
+        let signing_key_log: EventLog = EventLog {
+            standard: GATEWAY_STANDARD_NAME.to_string(),
+            version: GATEWAY_EVENT_VERSION.to_string(),
+            event,
+        };
+        env::log_str(&signing_key_log.to_string());
+    }
+}
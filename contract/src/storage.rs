@@ -0,0 +1,205 @@
+use crate::*;
+
+/// Bytes taken up by the storage balance of a registered account:
+/// the longest possible account ID, the balance and the collection overhead
+const STORAGE_BYTES_PER_ACCOUNT: u64 = 64 + 16 + 40;
+
+/// Storage balance of an account, as defined by NEP-145
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: U128,
+    // What is left once the registration is paid for, used to pay for storage and withdrawable
+    pub available: U128,
+}
+
+/// Minimum and maximum storage balance of an account, as defined by NEP-145
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+pub trait StorageManagement {
+    //deposit NEAR to pay for the storage of an account, registering it if needed
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance;
+
+    //withdraw NEAR that isn't needed to pay for storage
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance;
+
+    //unregister the caller and send back its whole storage balance
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool;
+
+    //get the storage balance bounds
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds;
+
+    //get the storage balance of an account
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance>;
+}
+
+#[near_bindgen]
+impl StorageManagement for Contract {
+    /// Deposit the attached NEAR to the storage balance of `account_id`, the caller by default.
+    /// The first deposit must cover the minimum balance. With `registration_only`,
+    /// only the minimum is kept for new accounts and anything else is refunded.
+    #[payable]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let amount = env::attached_deposit();
+        let min_balance = storage_balance_min();
+
+        let (balance, refund) = match self.storage_deposits.get(&account_id) {
+            Some(balance) if registration_only.unwrap_or(false) => (balance, amount),
+            Some(balance) => (balance + amount, 0),
+            None => {
+                require!(
+                    amount >= min_balance,
+                    format!(
+                        "Must attach at least {} yoctoNEAR to register the account",
+                        min_balance
+                    )
+                );
+                if registration_only.unwrap_or(false) {
+                    (min_balance, amount - min_balance)
+                } else {
+                    (amount, 0)
+                }
+            }
+        };
+        self.storage_deposits.insert(&account_id, &balance);
+
+        if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    /// Withdraw `amount` from the caller's available storage balance, all of it by default
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = self
+            .storage_deposits
+            .get(&account_id)
+            .expect("The account is not registered");
+
+        let available = balance - storage_balance_min();
+        let amount = amount.map(|amount| amount.0).unwrap_or(available);
+        require!(
+            amount <= available,
+            "The amount is greater than the available storage balance"
+        );
+        self.storage_deposits
+            .insert(&account_id, &(balance - amount));
+
+        if amount > 0 {
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    /// Unregister the caller and send back its whole storage balance.
+    /// Accounts that still hold tokens can only unregister with `force`, their tokens are kept.
+    /// Returns false if the account wasn't registered.
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = match self.storage_deposits.get(&account_id) {
+            Some(balance) => balance,
+            None => return false,
+        };
+
+        require!(
+            force.unwrap_or(false) || self.tokens_per_owner.get(&account_id).is_none(),
+            "Can't unregister an account that holds tokens without force"
+        );
+        self.storage_deposits.remove(&account_id);
+        Promise::new(account_id).transfer(balance);
+
+        true
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(storage_balance_min()),
+            max: None,
+        }
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_deposits
+            .get(&account_id)
+            .map(|balance| StorageBalance {
+                total: U128(balance),
+                available: U128(balance - storage_balance_min()),
+            })
+    }
+}
+
+impl Contract {
+    /// Charge `account_id` for `storage_used` bytes. Any attached deposit is used first and the excess
+    /// refunded to the caller, what it doesn't cover comes out of the account's available storage balance.
+    pub(crate) fn internal_charge_storage(&mut self, account_id: &AccountId, storage_used: u64) {
+        let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
+        let attached_deposit = env::attached_deposit();
+
+        if attached_deposit >= required_cost {
+            let refund = attached_deposit - required_cost;
+            if refund > 1 {
+                Promise::new(env::predecessor_account_id()).transfer(refund);
+            }
+            return;
+        }
+        let shortfall = required_cost - attached_deposit;
+        let balance = self.storage_deposits.get(account_id).unwrap_or(0);
+        require!(
+            balance >= storage_balance_min() + shortfall,
+            format!(
+                "Must attach {} yoctoNEAR or deposit it to the storage balance of {} to cover storage",
+                shortfall, account_id
+            )
+        );
+        self.storage_deposits
+            .insert(account_id, &(balance - shortfall));
+    }
+
+    /// Give back the cost of `storage_released` bytes to the account that paid for them,
+    /// on its storage balance if it is registered, as a transfer otherwise
+    pub(crate) fn internal_refund_storage(
+        &mut self,
+        account_id: &AccountId,
+        storage_released: u64,
+    ) {
+        let refund = env::storage_byte_cost() * Balance::from(storage_released);
+        if refund == 0 {
+            return;
+        }
+        match self.storage_deposits.get(account_id) {
+            Some(balance) => {
+                self.storage_deposits
+                    .insert(account_id, &(balance + refund));
+            }
+            None => {
+                Promise::new(account_id.clone()).transfer(refund);
+            }
+        }
+    }
+}
+
+//balance an account must keep to stay registered
+fn storage_balance_min() -> Balance {
+    env::storage_byte_cost() * Balance::from(STORAGE_BYTES_PER_ACCOUNT)
+}
@@ -1,11 +1,17 @@
 /* unit tests */
+use crate::approval::NonFungibleTokenCore as NonFungibleTokenApproval;
+use crate::nft_core::NonFungibleTokenCore;
 #[cfg(test)]
 use crate::Contract;
-use crate::TokenMetadata;
+use crate::StorageManagement;
+use crate::{MintVoucher, NonceScope, TokenMetadata};
+use ed25519_dalek::Signer;
 use near_sdk::json_types::{Base64VecU8, U128};
-use near_sdk::test_utils::{accounts, VMContextBuilder};
+use near_sdk::mock::VmAction;
+use near_sdk::test_utils::{accounts, get_created_receipts, get_logs, VMContextBuilder};
 use near_sdk::testing_env;
-use near_sdk::{env, AccountId};
+use near_sdk::{env, AccountId, ONE_NEAR};
+use std::collections::HashMap;
 
 // const MINT_STORAGE_COST: u128 = 100_000_000_000_000_000_000_000;
 // const MIN_REQUIRED_APPROVAL_YOCTO: u128 = 170000000000000000000;
@@ -15,6 +21,69 @@ fn get_context(predecessor: AccountId) -> VMContextBuilder {
     builder.predecessor_account_id(predecessor);
     builder
 }
+// deterministic signing key standing in for the owner's backend key
+fn owner_keypair() -> ed25519_dalek::Keypair {
+    let secret = ed25519_dalek::SecretKey::from_bytes(&[7u8; 32]).unwrap();
+    let public = ed25519_dalek::PublicKey::from(&secret);
+    ed25519_dalek::Keypair { secret, public }
+}
+fn owner_public_key() -> String {
+    bs58::encode(owner_keypair().public.as_bytes()).into_string()
+}
+// signing key of a series owner's backend
+fn creator_keypair() -> ed25519_dalek::Keypair {
+    let secret = ed25519_dalek::SecretKey::from_bytes(&[9u8; 32]).unwrap();
+    let public = ed25519_dalek::PublicKey::from(&secret);
+    ed25519_dalek::Keypair { secret, public }
+}
+fn creator_public_key() -> String {
+    bs58::encode(creator_keypair().public.as_bytes()).into_string()
+}
+fn sample_voucher(
+    method: &str,
+    series_id: u64,
+    receiver_id: AccountId,
+    amount: u32,
+) -> MintVoucher {
+    MintVoucher {
+        contract_id: accounts(0),
+        method: method.to_string(),
+        series_id,
+        receiver_id,
+        amount,
+        nonce: 1,
+        expires_at: 1_000,
+    }
+}
+fn sign_voucher(voucher: &MintVoucher) -> Vec<u8> {
+    owner_keypair().sign(&voucher.hash()).to_bytes().to_vec()
+}
+// contract owned by accounts(0) with a single series created
+fn setup_contract_with_series() -> (VMContextBuilder, Contract) {
+    let mut context = get_context(accounts(0));
+    context.current_account_id(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0), owner_public_key());
+    register_storage(
+        &mut context,
+        &mut contract,
+        &[accounts(0), accounts(1), accounts(2), accounts(3)],
+    );
+    contract.create_series(1, sample_token_metadata(), None, None, None, None);
+    (context, contract)
+}
+// deposit a storage balance for each account, leaving the context as it was with no deposit attached
+fn register_storage(
+    context: &mut VMContextBuilder,
+    contract: &mut Contract,
+    account_ids: &[AccountId],
+) {
+    for account_id in account_ids {
+        testing_env!(context.attached_deposit(ONE_NEAR).build());
+        contract.storage_deposit(Some(account_id.clone()), None);
+    }
+    testing_env!(context.attached_deposit(0).build());
+}
 fn sample_token_metadata() -> TokenMetadata {
     TokenMetadata {
         title: Some("Blue Badge".into()),
@@ -61,10 +130,11 @@ fn test_create_badge_with_wrong_acct_collection() {
         accounts(0).into(),
         "8QoJVEQAstCiSU4osfagAMZQqUpoYnvj1K8kgczhSE4e".to_string(),
     );
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
 
     let token_metadata: TokenMetadata = sample_token_metadata();
     testing_env!(context.predecessor_account_id(accounts(1)).build());
-    contract.create_series(1, token_metadata, None, None);
+    contract.create_series(1, token_metadata, None, None, None, None);
 }
 #[test]
 fn test_create_series() {
@@ -74,12 +144,13 @@ fn test_create_series() {
         accounts(0).into(),
         "8QoJVEQAstCiSU4osfagAMZQqUpoYnvj1K8kgczhSE4e".to_string(),
     );
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
 
     testing_env!(context.predecessor_account_id(accounts(0)).build());
-    let series_id = 1;
     let token_metadata: TokenMetadata = sample_token_metadata();
 
-    contract.create_series(1, token_metadata, None, None);
+    let series_id = contract.create_series(1, token_metadata, None, None, None, None);
+    assert_eq!(series_id, 1);
     let created_series = contract.get_series_details(series_id).unwrap();
     // println!("{:?}", );
     assert_eq!(created_series.series_id, series_id);
@@ -94,12 +165,13 @@ fn test_mint_badge() {
         accounts(0).into(),
         "8QoJVEQAstCiSU4osfagAMZQqUpoYnvj1K8kgczhSE4e".to_string(),
     );
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
 
     testing_env!(context.predecessor_account_id(accounts(0)).build());
     let series_id = 1;
     let token_metadata: TokenMetadata = sample_token_metadata();
 
-    contract.create_series(1, token_metadata, None, None);
+    contract.create_series(1, token_metadata, None, None, None, None);
 
     contract.badge_mint_test(series_id.into(), accounts(1));
 
@@ -124,12 +196,13 @@ pub fn test_update_series_media() {
         accounts(0).into(),
         "8QoJVEQAstCiSU4osfagAMZQqUpoYnvj1K8kgczhSE4e".to_string(),
     );
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
 
     testing_env!(context.predecessor_account_id(accounts(0)).build());
-    let series_id = 1;
     let token_metadata: TokenMetadata = sample_token_metadata();
 
-    contract.create_series(1, token_metadata, None, None);
+    let series_id = contract.create_series(1, token_metadata, None, None, None, None);
+    assert_eq!(series_id, 1);
     let created_series = contract.get_series_details(series_id).unwrap();
     // println!("{:?}", );
     assert_eq!(created_series.series_id, series_id);
@@ -158,13 +231,14 @@ fn test_fail_get_badge_series_by_type() {
         accounts(0).into(),
         "8QoJVEQAstCiSU4osfagAMZQqUpoYnvj1K8kgczhSE4e".to_string(),
     );
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
 
     testing_env!(context.predecessor_account_id(accounts(0)).build());
 
     let token_metadata: TokenMetadata = sample_token_metadata();
 
     let series_type = 3u8;
-    contract.create_series(series_type, token_metadata, None, None);
+    contract.create_series(series_type, token_metadata, None, None, None, None);
 }
 
 #[test]
@@ -175,13 +249,14 @@ fn test_get_badge_series_by_type() {
         accounts(0).into(),
         "8QoJVEQAstCiSU4osfagAMZQqUpoYnvj1K8kgczhSE4e".to_string(),
     );
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
 
     testing_env!(context.predecessor_account_id(accounts(0)).build());
 
     let token_metadata: TokenMetadata = sample_token_metadata();
 
-    contract.create_series(1u8, token_metadata.clone(), None, None);
-    contract.create_series(2u8, token_metadata, None, None);
+    contract.create_series(1u8, token_metadata.clone(), None, None, None, None);
+    contract.create_series(2u8, token_metadata, None, None, None, None);
 
     let series_1 = contract.get_badge_series_by_type(1);
     let series_2 = contract.get_badge_series_by_type(2);
@@ -195,3 +270,2392 @@ fn test_test() {
     let acctId = AccountId::new_unchecked((&"unrecoverable_burn_account").to_string());
     println!("{}", acctId);
 }
+
+#[test]
+fn test_mint_badge_with_voucher() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    contract.mint_badge(1, accounts(1), voucher, signature);
+
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 1.into());
+    assert_eq!(contract.get_nonce(&accounts(1)), 1);
+}
+
+#[test]
+fn test_batch_mint_with_voucher() {
+    let (context, mut contract) = setup_contract_with_series();
+
+    let voucher = sample_voucher("batch_mint", 1, accounts(1), 3);
+    let signature = sign_voucher(&voucher);
+    testing_env!(context.build());
+    contract.batch_mint(
+        1,
+        3,
+        accounts(1),
+        voucher,
+        signature,
+        Some("welcome pack".to_string()),
+    );
+
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 3.into());
+    assert_eq!(contract.get_nonce(&accounts(1)), 1);
+    // a single log entry for all the tokens of the batch
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"bob","token_ids":["1:1","1:2","1:3"],"memo":"welcome pack"}]}"#
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Voucher was issued for another contract")]
+fn test_voucher_for_another_contract() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let mut voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    voucher.contract_id = accounts(2);
+    let signature = sign_voucher(&voucher);
+    contract.mint_badge(1, accounts(1), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Voucher was issued for method mint_badge")]
+fn test_voucher_for_another_method() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    contract.batch_mint(1, 1, accounts(1), voucher, signature, None);
+}
+
+#[test]
+#[should_panic(expected = "Voucher was issued for another series")]
+fn test_voucher_for_another_series() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.create_series(1, sample_token_metadata(), None, None, None, None);
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    contract.mint_badge(2, accounts(1), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Voucher was issued for another receiver")]
+fn test_voucher_for_another_receiver() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    contract.mint_badge(1, accounts(2), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Voucher was issued for another amount")]
+fn test_voucher_for_another_amount() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let voucher = sample_voucher("batch_mint", 1, accounts(1), 2);
+    let signature = sign_voucher(&voucher);
+    contract.batch_mint(1, 5, accounts(1), voucher, signature, None);
+}
+
+#[test]
+#[should_panic(expected = "Voucher expired")]
+fn test_expired_voucher() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    // block timestamp is in nanoseconds, expiry in milliseconds
+    testing_env!(context.block_timestamp(1_001 * 1_000_000).build());
+    contract.mint_badge(1, accounts(1), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Nonce 1 was already used")]
+fn test_replayed_voucher() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    contract.mint_badge(1, accounts(1), voucher.clone(), signature.clone());
+    contract.mint_badge(1, accounts(1), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: invalid voucher signature")]
+fn test_tampered_voucher() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let mut voucher = sample_voucher("batch_mint", 1, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    // the signature no longer covers the voucher once any field changes
+    voucher.amount = 5;
+    contract.batch_mint(1, 5, accounts(1), voucher, signature, None);
+}
+
+#[test]
+fn test_nft_mint_paid() {
+    let mut context = get_context(accounts(0));
+    context.current_account_id(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0), owner_public_key());
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
+    contract.create_series(
+        1,
+        sample_token_metadata(),
+        None,
+        Some(U128(ONE_NEAR)),
+        None,
+        None,
+    );
+    assert_eq!(
+        contract.get_series_details(1).unwrap().price,
+        Some(U128(ONE_NEAR))
+    );
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(2 * ONE_NEAR)
+        .build());
+    let token_id = contract.nft_mint_paid(1, accounts(2));
+
+    assert_eq!(token_id, "1:1");
+    assert_eq!(contract.nft_supply_for_owner(accounts(2)), 1.into());
+
+    // the price goes to the series owner, the rest minus storage is refunded to the buyer
+    let receipts = get_created_receipts();
+    assert_eq!(receipts.len(), 2);
+    assert_eq!(receipts[0].receiver_id, accounts(0));
+    assert_eq!(
+        receipts[0].actions,
+        vec![VmAction::Transfer { deposit: ONE_NEAR }]
+    );
+    assert_eq!(receipts[1].receiver_id, accounts(1));
+    match receipts[1].actions[0] {
+        VmAction::Transfer { deposit } => assert!(deposit > 0 && deposit < ONE_NEAR),
+        _ => panic!("expected a refund"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "Series is not for sale")]
+fn test_nft_mint_paid_without_price() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.attached_deposit(ONE_NEAR).build());
+    contract.nft_mint_paid(1, accounts(1));
+}
+
+#[test]
+#[should_panic(expected = "to cover storage and price per token")]
+fn test_nft_mint_paid_insufficient_deposit() {
+    let mut context = get_context(accounts(0));
+    context.current_account_id(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0), owner_public_key());
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
+    contract.create_series(
+        1,
+        sample_token_metadata(),
+        None,
+        Some(U128(ONE_NEAR)),
+        None,
+        None,
+    );
+
+    testing_env!(context.attached_deposit(ONE_NEAR).build());
+    contract.nft_mint_paid(1, accounts(1));
+}
+
+// series 1 is owned by accounts(1), an approved creator that isn't the contract owner
+fn setup_contract_with_creator_series() -> (VMContextBuilder, Contract) {
+    let mut context = get_context(accounts(0));
+    context.current_account_id(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0), owner_public_key());
+    contract.add_approved_creator(accounts(1));
+    register_storage(
+        &mut context,
+        &mut contract,
+        &[accounts(0), accounts(1), accounts(2), accounts(3)],
+    );
+
+    testing_env!(context
+        .current_account_id(accounts(1))
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.create_series(1, sample_token_metadata(), None, None, None, None);
+    testing_env!(context
+        .current_account_id(accounts(0))
+        .predecessor_account_id(accounts(0))
+        .build());
+    (context, contract)
+}
+
+#[test]
+fn test_update_series_metadata() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+    contract.badge_mint_test(1.into(), accounts(3));
+    contract.badge_mint_test(1.into(), accounts(3));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    let mut metadata = sample_token_metadata();
+    metadata.title = Some("Gold Badge".into());
+    metadata.extra = Some("{\"level\":2}".into());
+    metadata.copies = Some(2);
+    let mut royalty = HashMap::new();
+    royalty.insert(accounts(1), 500);
+    contract.update_series_metadata(1, metadata, Some(royalty.clone()));
+
+    let series = contract.get_series_details(1).unwrap();
+    assert_eq!(series.metadata.title, Some("Gold Badge".into()));
+    assert_eq!(series.metadata.extra, Some("{\"level\":2}".into()));
+    assert_eq!(series.metadata.copies, Some(2));
+    assert_eq!(series.royalty, Some(royalty));
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"series_metadata_update","data":[{"series_id":1,"token_count":2}]}"#,
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_metadata_update","data":[{"token_ids":["1:1","1:2"]}]}"#
+        ]
+    );
+}
+
+#[test]
+fn test_update_series_metadata_logs_every_token() {
+    let (context, mut contract) = setup_contract_with_series();
+    let mut metadata = sample_token_metadata();
+    metadata.copies = None;
+    contract.update_series_metadata(1, metadata.clone(), None);
+    for _ in 0..150 {
+        testing_env!(context.build());
+        contract.badge_mint_test(1.into(), accounts(3));
+    }
+
+    testing_env!(context.build());
+    contract.update_series_metadata(1, metadata, None);
+
+    let logs = get_logs();
+    assert_eq!(logs.len(), 3);
+    assert_eq!(
+        logs[0],
+        r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"series_metadata_update","data":[{"series_id":1,"token_count":150}]}"#
+    );
+    // every token is listed, 100 per log
+    let logged_token_ids: Vec<String> = logs[1..]
+        .iter()
+        .flat_map(|log| {
+            let event: near_sdk::serde_json::Value =
+                near_sdk::serde_json::from_str(log.trim_start_matches("EVENT_JSON:")).unwrap();
+            event["data"][0]["token_ids"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|token_id| token_id.as_str().unwrap().to_string())
+                .collect::<Vec<String>>()
+        })
+        .collect();
+    assert_eq!(logged_token_ids.len(), 150);
+    for edition in 1..=150 {
+        assert!(logged_token_ids.contains(&format!("1:{}", edition)));
+    }
+}
+
+#[test]
+fn test_update_series_metadata_too_large_to_list() {
+    let (context, mut contract) = setup_contract_with_series();
+    let mut metadata = sample_token_metadata();
+    metadata.copies = None;
+    contract.update_series_metadata(1, metadata.clone(), None);
+    for _ in 0..(crate::MAX_TOKEN_IDS_PER_LOG * crate::MAX_METADATA_UPDATE_LOGS + 1) {
+        testing_env!(context.build());
+        contract.badge_mint_test(1.into(), accounts(3));
+    }
+
+    testing_env!(context.build());
+    contract.update_series_metadata(1, metadata, None);
+
+    // no partial nft_metadata_update event, only the series one
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"series_metadata_update","data":[{"series_id":1,"token_count":501}]}"#
+        ]
+    );
+}
+
+#[test]
+fn test_contract_owner_can_update_series_metadata() {
+    let (_, mut contract) = setup_contract_with_creator_series();
+
+    let mut metadata = sample_token_metadata();
+    metadata.description = Some("moderated".into());
+    contract.update_series_metadata(1, metadata, None);
+
+    let series = contract.get_series_details(1).unwrap();
+    assert_eq!(series.metadata.description, Some("moderated".into()));
+}
+
+#[test]
+#[should_panic(expected = "only the series owner can update the series")]
+fn test_other_creator_cannot_update_series_metadata() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+    contract.add_approved_creator(accounts(2));
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.update_series_metadata(1, sample_token_metadata(), None);
+}
+
+#[test]
+#[should_panic(expected = "only the series owner can update the series")]
+fn test_other_creator_cannot_update_series_media() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+    contract.add_approved_creator(accounts(2));
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.update_badge_collection_media(1.into(), Some("https://images.com/2.png".into()), None);
+}
+
+#[test]
+#[should_panic(expected = "copies cannot be lower than the number of tokens already minted")]
+fn test_update_series_copies_below_minted() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+    contract.badge_mint_test(1.into(), accounts(3));
+    contract.badge_mint_test(1.into(), accounts(3));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    let mut metadata = sample_token_metadata();
+    metadata.copies = Some(1);
+    contract.update_series_metadata(1, metadata, None);
+}
+
+#[test]
+fn test_transfer_series_ownership() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.propose_series_owner(1, accounts(2));
+    let series = contract.get_series_details(1).unwrap();
+    assert_eq!(series.owner_id, accounts(1));
+    assert_eq!(series.pending_owner_id, Some(accounts(2)));
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.accept_series_owner(1);
+    let series = contract.get_series_details(1).unwrap();
+    assert_eq!(series.owner_id, accounts(2));
+    assert_eq!(series.pending_owner_id, None);
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"series_owner_transferred","data":[{"series_id":1,"old_owner_id":"bob","new_owner_id":"charlie"}]}"#
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "only the series owner can update the series")]
+fn test_previous_series_owner_loses_access() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.propose_series_owner(1, accounts(2));
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.accept_series_owner(1);
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.update_series_metadata(1, sample_token_metadata(), None);
+}
+
+#[test]
+#[should_panic(expected = "only the proposed owner can accept the series")]
+fn test_accept_series_owner_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.propose_series_owner(1, accounts(2));
+
+    testing_env!(context.predecessor_account_id(accounts(3)).build());
+    contract.accept_series_owner(1);
+}
+
+#[test]
+#[should_panic(expected = "only the proposed owner can accept the series")]
+fn test_cancel_series_owner_proposal() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.propose_series_owner(1, accounts(2));
+    contract.cancel_series_owner_proposal(1);
+    assert_eq!(
+        contract.get_series_details(1).unwrap().pending_owner_id,
+        None
+    );
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.accept_series_owner(1);
+}
+
+#[test]
+#[should_panic(expected = "only the series owner can update the series")]
+fn test_propose_series_owner_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.propose_series_owner(1, accounts(2));
+}
+
+#[test]
+fn test_series_lifecycle() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.create_series(1, sample_token_metadata(), None, None, Some(1), None);
+    assert_eq!(contract.get_series_details(1).unwrap().status, 2);
+    assert_eq!(contract.get_series_details(2).unwrap().status, 1);
+
+    contract.set_series_status(2, 2);
+    contract.badge_mint_test(2.into(), accounts(1));
+    contract.set_series_status(2, 3);
+    contract.set_series_status(2, 2);
+    contract.badge_mint_test(2.into(), accounts(1));
+    contract.set_series_status(2, 4);
+
+    assert_eq!(contract.get_series_details(2).unwrap().status, 4);
+    assert_eq!(contract.nft_supply_for_series(2), 2.into());
+
+    let active = contract.get_series_by_status(2, None, None);
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].series_id, 1);
+    let closed = contract.get_series_by_status(4, None, None);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].series_id, 2);
+}
+
+#[test]
+#[should_panic(expected = "Series is still a draft")]
+fn test_mint_from_draft_series() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.create_series(1, sample_token_metadata(), None, None, Some(1), None);
+
+    let voucher = sample_voucher("mint_badge", 2, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    contract.mint_badge(2, accounts(1), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Series is paused")]
+fn test_mint_from_paused_series() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_series_status(1, 3);
+
+    let voucher = sample_voucher("batch_mint", 1, accounts(1), 2);
+    let signature = sign_voucher(&voucher);
+    contract.batch_mint(1, 2, accounts(1), voucher, signature, None);
+}
+
+#[test]
+#[should_panic(expected = "Series is closed")]
+fn test_mint_from_closed_series() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_series_status(1, 4);
+
+    contract.badge_mint_test(1.into(), accounts(1));
+}
+
+#[test]
+#[should_panic(expected = "Cannot change series status from 4 to 2")]
+fn test_reopen_closed_series() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_series_status(1, 4);
+    contract.set_series_status(1, 2);
+}
+
+#[test]
+#[should_panic(expected = "only the series owner can update the series")]
+fn test_set_series_status_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.set_series_status(1, 3);
+}
+
+#[test]
+fn test_series_mint_window() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_mint_window(1, Some(1_000), Some(2_000));
+    let series = contract.get_series_details(1).unwrap();
+    assert_eq!(series.mint_starts_at, Some(1_000));
+    assert_eq!(series.mint_ends_at, Some(2_000));
+
+    // block timestamp is in nanoseconds, the window in milliseconds
+    testing_env!(context.block_timestamp(999 * 1_000_000).build());
+    let mintability = contract.get_series_mintability(1);
+    assert!(!mintability.mintable);
+    assert_eq!(
+        mintability.reason,
+        Some("Minting has not started for this series".to_string())
+    );
+
+    testing_env!(context.block_timestamp(1_000 * 1_000_000).build());
+    let mintability = contract.get_series_mintability(1);
+    assert!(mintability.mintable);
+    assert_eq!(mintability.reason, None);
+    contract.badge_mint_test(1.into(), accounts(1));
+
+    testing_env!(context.block_timestamp(2_000 * 1_000_000).build());
+    let mintability = contract.get_series_mintability(1);
+    assert!(!mintability.mintable);
+    assert_eq!(
+        mintability.reason,
+        Some("Minting has ended for this series".to_string())
+    );
+
+    assert_eq!(
+        contract.get_series_mintability(7).reason,
+        Some("Not a series".to_string())
+    );
+}
+
+#[test]
+#[should_panic(expected = "Minting has ended for this series")]
+fn test_mint_after_mint_window() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_mint_window(1, None, Some(500));
+
+    testing_env!(context.block_timestamp(600 * 1_000_000).build());
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    let signature = sign_voucher(&voucher);
+    contract.mint_badge(1, accounts(1), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Mint window must start before it ends")]
+fn test_invalid_mint_window() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_series_mint_window(1, Some(2_000), Some(1_000));
+}
+
+// series 1 is soulbound and accounts(1) holds its first token
+fn setup_contract_with_soulbound_token() -> (VMContextBuilder, Contract) {
+    let mut context = get_context(accounts(0));
+    context.current_account_id(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0), owner_public_key());
+    register_storage(&mut context, &mut contract, &[accounts(0)]);
+    contract.create_series(1, sample_token_metadata(), None, None, None, Some(true));
+    contract.badge_mint_test(1.into(), accounts(1));
+    (context, contract)
+}
+
+#[test]
+fn test_soulbound_series() {
+    let (_, contract) = setup_contract_with_soulbound_token();
+
+    assert!(contract.get_series_details(1).unwrap().soulbound);
+    assert!(contract.nft_token("1:1".to_string()).unwrap().soulbound);
+}
+
+#[test]
+#[should_panic(expected = "Soulbound tokens cannot be transferred")]
+fn test_transfer_soulbound_token() {
+    let (mut context, mut contract) = setup_contract_with_soulbound_token();
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(1)
+        .build());
+    contract.nft_transfer(accounts(2), "1:1".to_string(), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Soulbound tokens cannot be approved")]
+fn test_approve_soulbound_token() {
+    let (mut context, mut contract) = setup_contract_with_soulbound_token();
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(ONE_NEAR)
+        .build());
+    contract.nft_approve("1:1".to_string(), accounts(2), None);
+}
+
+#[test]
+fn test_recover_soulbound_token() {
+    let (_, mut contract) = setup_contract_with_soulbound_token();
+
+    contract.recover_token(
+        "1:1".to_string(),
+        accounts(2),
+        Some("lost keys".to_string()),
+    );
+
+    assert_eq!(
+        contract.nft_token("1:1".to_string()).unwrap().owner_id,
+        accounts(2)
+    );
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 0.into());
+    assert_eq!(contract.nft_supply_for_owner(accounts(2)), 1.into());
+}
+
+#[test]
+#[should_panic(expected = "only contract owner")]
+fn test_recover_token_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_soulbound_token();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.recover_token("1:1".to_string(), accounts(2), None);
+}
+
+#[test]
+fn test_burn_soulbound_token() {
+    let (mut context, mut contract) = setup_contract_with_soulbound_token();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.batch_burn(
+        1,
+        Some(1),
+        owner_public_key(),
+        vec![],
+        Some(accounts(1)),
+        None,
+    );
+
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 0.into());
+}
+
+#[test]
+fn test_series_max_per_account() {
+    let (_, mut contract) = setup_contract_with_series();
+    assert_eq!(contract.get_remaining_mint_allowance(1, accounts(1)), None);
+
+    contract.set_series_max_per_account(1, Some(2));
+    assert_eq!(
+        contract.get_series_details(1).unwrap().max_per_account,
+        Some(2)
+    );
+    assert!(!contract.get_series_details(1).unwrap().unique);
+    assert_eq!(
+        contract.get_remaining_mint_allowance(1, accounts(1)),
+        Some(2)
+    );
+
+    contract.badge_mint_test(1.into(), accounts(1));
+    assert_eq!(
+        contract.get_remaining_mint_allowance(1, accounts(1)),
+        Some(1)
+    );
+    contract.badge_mint_test(1.into(), accounts(1));
+    assert_eq!(
+        contract.get_remaining_mint_allowance(1, accounts(1)),
+        Some(0)
+    );
+    assert_eq!(
+        contract.get_remaining_mint_allowance(1, accounts(2)),
+        Some(2)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Mint limit per account reached for this series")]
+fn test_unique_series() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_series_max_per_account(1, Some(1));
+    assert!(contract.get_series_details(1).unwrap().unique);
+
+    let voucher = sample_voucher("batch_mint", 1, accounts(1), 2);
+    let signature = sign_voucher(&voucher);
+    contract.batch_mint(1, 2, accounts(1), voucher, signature, None);
+}
+
+#[test]
+#[should_panic(expected = "Mint limit per account reached for this series")]
+fn test_max_per_account_counts_transferred_tokens() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_max_per_account(1, Some(1));
+    contract.badge_mint_test(1.into(), accounts(1));
+
+    // moving the token away doesn't give the account a new allowance
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(1)
+        .build());
+    contract.nft_transfer(accounts(2), "1:1".to_string(), None, None);
+    assert_eq!(
+        contract.get_remaining_mint_allowance(1, accounts(1)),
+        Some(0)
+    );
+
+    contract.badge_mint_test(1.into(), accounts(1));
+}
+
+#[test]
+fn test_max_per_account_ignores_received_tokens() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_max_per_account(1, Some(1));
+    contract.badge_mint_test(1.into(), accounts(1));
+
+    // receiving a token doesn't use up the receiver's allowance
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(1)
+        .build());
+    contract.nft_transfer(accounts(2), "1:1".to_string(), None, None);
+    assert_eq!(
+        contract.get_remaining_mint_allowance(1, accounts(2)),
+        Some(1)
+    );
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.badge_mint_test(1.into(), accounts(2));
+    assert_eq!(
+        contract.get_remaining_mint_allowance(1, accounts(2)),
+        Some(0)
+    );
+}
+
+// allowlist of accounts(1), accounts(2) with a quantity of 3, accounts(3) and accounts(4)
+fn sample_allowlist_leaves() -> Vec<Vec<u8>> {
+    vec![
+        env::sha256(accounts(1).as_bytes()),
+        env::sha256(format!("{}:3", accounts(2)).as_bytes()),
+        env::sha256(accounts(3).as_bytes()),
+        env::sha256(accounts(4).as_bytes()),
+    ]
+}
+fn merkle_pair(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    env::sha256(&[first, second].concat())
+}
+fn sample_allowlist_root() -> Base64VecU8 {
+    let leaves = sample_allowlist_leaves();
+    merkle_pair(
+        &merkle_pair(&leaves[0], &leaves[1]),
+        &merkle_pair(&leaves[2], &leaves[3]),
+    )
+    .into()
+}
+fn sample_allowlist_proof(index: usize) -> Vec<Base64VecU8> {
+    let leaves = sample_allowlist_leaves();
+    let sibling = leaves[index ^ 1].clone();
+    let other_pair = if index < 2 {
+        merkle_pair(&leaves[2], &leaves[3])
+    } else {
+        merkle_pair(&leaves[0], &leaves[1])
+    };
+    vec![sibling.into(), other_pair.into()]
+}
+
+#[test]
+fn test_mint_with_proof() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_allowlist(1, Some(sample_allowlist_root()));
+    assert_eq!(
+        contract.get_series_details(1).unwrap().allowlist_root,
+        Some(sample_allowlist_root())
+    );
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    let token_ids = contract.mint_with_proof(1, sample_allowlist_proof(0), 1, None);
+    assert_eq!(token_ids, vec!["1:1".to_string()]);
+    assert_eq!(contract.get_allowlist_claimed(1, accounts(1)), 1);
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    let token_ids = contract.mint_with_proof(1, sample_allowlist_proof(1), 2, Some(3));
+    assert_eq!(token_ids, vec!["1:2".to_string(), "1:3".to_string()]);
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"charlie","token_ids":["1:2","1:3"]}]}"#
+        ]
+    );
+    contract.mint_with_proof(1, sample_allowlist_proof(1), 1, Some(3));
+    assert_eq!(contract.get_allowlist_claimed(1, accounts(2)), 3);
+    assert_eq!(contract.nft_supply_for_owner(accounts(2)), 3.into());
+}
+
+#[test]
+#[should_panic(expected = "Allowlist allowance exceeded, 1 of 1 already claimed")]
+fn test_mint_with_proof_twice() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_allowlist(1, Some(sample_allowlist_root()));
+
+    testing_env!(context.predecessor_account_id(accounts(3)).build());
+    contract.mint_with_proof(1, sample_allowlist_proof(2), 1, None);
+    contract.mint_with_proof(1, sample_allowlist_proof(2), 1, None);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: invalid allowlist proof")]
+fn test_mint_with_proof_not_on_allowlist() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_allowlist(1, Some(sample_allowlist_root()));
+
+    testing_env!(context.predecessor_account_id(accounts(5)).build());
+    contract.mint_with_proof(1, sample_allowlist_proof(0), 1, None);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: invalid allowlist proof")]
+fn test_mint_with_proof_wrong_quantity() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_allowlist(1, Some(sample_allowlist_root()));
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.mint_with_proof(1, sample_allowlist_proof(1), 5, Some(5));
+}
+
+#[test]
+#[should_panic(expected = "Amount must be greater than 0")]
+fn test_mint_with_proof_zero_amount() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_allowlist(1, Some(sample_allowlist_root()));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.mint_with_proof(1, sample_allowlist_proof(0), 0, None);
+}
+
+fn claim_code_hash(code: &str) -> Base64VecU8 {
+    env::sha256(code.as_bytes()).into()
+}
+
+#[test]
+fn test_claim_with_code() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.add_claim_codes(
+        1,
+        vec![
+            claim_code_hash("booth-1"),
+            claim_code_hash("booth-2"),
+            claim_code_hash("booth-3"),
+        ],
+    );
+    assert_eq!(contract.get_unclaimed_code_count(1), 3);
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    let token_id = contract.claim_with_code(1, "booth-2".to_string(), accounts(1));
+    assert_eq!(token_id, "1:1");
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 1.into());
+    assert_eq!(contract.get_unclaimed_code_count(1), 2);
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    let revoked = contract.revoke_claim_codes(
+        1,
+        vec![claim_code_hash("booth-1"), claim_code_hash("booth-2")],
+    );
+    assert_eq!(revoked, 1);
+    assert_eq!(contract.get_unclaimed_code_count(1), 1);
+}
+
+#[test]
+#[should_panic(expected = "Invalid or already used claim code")]
+fn test_claim_code_used_twice() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.add_claim_codes(1, vec![claim_code_hash("booth-1")]);
+
+    contract.claim_with_code(1, "booth-1".to_string(), accounts(1));
+    contract.claim_with_code(1, "booth-1".to_string(), accounts(2));
+}
+
+#[test]
+#[should_panic(expected = "Invalid or already used claim code")]
+fn test_claim_revoked_code() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.add_claim_codes(1, vec![claim_code_hash("booth-1")]);
+    contract.revoke_claim_codes(1, vec![claim_code_hash("booth-1")]);
+
+    contract.claim_with_code(1, "booth-1".to_string(), accounts(1));
+}
+
+#[test]
+#[should_panic(expected = "Claim code was already used")]
+fn test_register_used_claim_code() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.add_claim_codes(1, vec![claim_code_hash("booth-1")]);
+    contract.claim_with_code(1, "booth-1".to_string(), accounts(1));
+
+    contract.add_claim_codes(1, vec![claim_code_hash("booth-1")]);
+}
+
+#[test]
+#[should_panic(expected = "only the series owner can update the series")]
+fn test_add_claim_codes_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.add_claim_codes(1, vec![claim_code_hash("booth-1")]);
+}
+
+#[test]
+fn test_mint_with_series_minting_key() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.add_series_minting_key(1, creator_public_key());
+    assert_eq!(
+        contract.get_series_minting_keys(1),
+        vec![creator_public_key()]
+    );
+    assert_eq!(
+        get_logs(),
+        vec![format!(
+            r#"EVENT_JSON:{{"standard":"gateway","version":"1.0.0","event":"series_minting_key_added","data":[{{"series_id":1,"public_key":"{}"}}]}}"#,
+            creator_public_key()
+        )]
+    );
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(3), 1);
+    let signature = creator_keypair().sign(&voucher.hash()).to_bytes().to_vec();
+    contract.mint_badge(1, accounts(3), voucher, signature);
+    assert_eq!(contract.nft_supply_for_owner(accounts(3)), 1.into());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: invalid voucher signature")]
+fn test_series_minting_key_cannot_sign_for_other_series() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+    contract.create_series(1, sample_token_metadata(), None, None, None, None);
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.add_series_minting_key(1, creator_public_key());
+
+    let voucher = sample_voucher("mint_badge", 2, accounts(3), 1);
+    let signature = creator_keypair().sign(&voucher.hash()).to_bytes().to_vec();
+    contract.mint_badge(2, accounts(3), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: invalid voucher signature")]
+fn test_removed_series_minting_key() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.add_series_minting_key(1, creator_public_key());
+    contract.remove_series_minting_key(1, creator_public_key());
+    assert!(contract.get_series_minting_keys(1).is_empty());
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(3), 1);
+    let signature = creator_keypair().sign(&voucher.hash()).to_bytes().to_vec();
+    contract.mint_badge(1, accounts(3), voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Public key should be base58 encoded")]
+fn test_add_invalid_series_minting_key() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.add_series_minting_key(1, "not-a-key!".to_string());
+}
+
+#[test]
+#[should_panic(expected = "only the series owner can update the series")]
+fn test_add_series_minting_key_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.add_series_minting_key(1, creator_public_key());
+}
+
+#[test]
+fn test_rotate_signing_key() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.add_signing_key("backend-2".to_string(), creator_public_key(), None);
+    contract.retire_signing_key("default".to_string());
+    assert_eq!(
+        get_logs()[0],
+        format!(
+            r#"EVENT_JSON:{{"standard":"gateway","version":"1.0.0","event":"signing_key_added","data":[{{"key_id":"backend-2","public_key":"{}"}}]}}"#,
+            creator_public_key()
+        )
+    );
+    let signing_keys = contract.get_signing_keys();
+    assert_eq!(signing_keys.len(), 2);
+    assert!(!signing_keys[0].active);
+    assert!(signing_keys[1].active);
+    assert_eq!(contract.owner_public_key(), Some(creator_public_key()));
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(3), 1);
+    let signature = creator_keypair().sign(&voucher.hash()).to_bytes().to_vec();
+    contract.mint_badge(1, accounts(3), voucher, signature);
+    assert_eq!(contract.nft_supply_for_owner(accounts(3)), 1.into());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: invalid voucher signature")]
+fn test_retired_signing_key() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.add_signing_key("backend-2".to_string(), creator_public_key(), None);
+    contract.retire_signing_key("default".to_string());
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(3), 1);
+    contract.mint_badge(1, accounts(3), voucher.clone(), sign_voucher(&voucher));
+}
+
+#[test]
+fn test_signing_key_grace_period() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    // the old key is still accepted until it expires
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.add_signing_key("backend-2".to_string(), creator_public_key(), None);
+    contract.set_signing_key_expiry("default".to_string(), Some(500));
+    assert!(contract.get_signing_keys()[0].active);
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(3), 1);
+    contract.mint_badge(1, accounts(3), voucher.clone(), sign_voucher(&voucher));
+    assert_eq!(contract.nft_supply_for_owner(accounts(3)), 1.into());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: invalid voucher signature")]
+fn test_expired_signing_key() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.set_signing_key_expiry("default".to_string(), Some(500));
+
+    testing_env!(context.block_timestamp(500 * 1_000_000).build());
+    assert!(!contract.get_signing_keys()[0].active);
+    assert_eq!(contract.owner_public_key(), None);
+    let voucher = sample_voucher("mint_badge", 1, accounts(3), 1);
+    contract.mint_badge(1, accounts(3), voucher.clone(), sign_voucher(&voucher));
+}
+
+#[test]
+#[should_panic(expected = "Public key should be a valid ed25519 key")]
+fn test_add_invalid_signing_key() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.add_signing_key("backend-2".to_string(), "3yZe7d".to_string(), None);
+}
+
+#[test]
+#[should_panic(expected = "Signing key ID already exists")]
+fn test_add_duplicate_signing_key_id() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.add_signing_key("default".to_string(), creator_public_key(), None);
+}
+
+#[test]
+#[should_panic(expected = "only contract owner")]
+fn test_add_signing_key_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.add_signing_key("backend-2".to_string(), creator_public_key(), None);
+}
+
+#[test]
+fn test_vouchers_redeemed_out_of_order() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let mut second = sample_voucher("mint_badge", 1, accounts(1), 1);
+    second.nonce = 2;
+    contract.mint_badge(1, accounts(1), second.clone(), sign_voucher(&second));
+    assert_eq!(contract.get_nonce(&accounts(1)), 2);
+    assert!(!contract.is_nonce_used(accounts(1), 1, 1));
+
+    let first = sample_voucher("mint_badge", 1, accounts(1), 1);
+    contract.mint_badge(1, accounts(1), first.clone(), sign_voucher(&first));
+    assert!(contract.is_nonce_used(accounts(1), 1, 1));
+    assert_eq!(contract.get_nonce(&accounts(1)), 2);
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 2.into());
+}
+
+// implicit account of the owner's key, as used by withdraw and burn
+fn owner_implicit_account() -> AccountId {
+    AccountId::new_unchecked(hex::encode(owner_keypair().public.as_bytes()))
+}
+fn sign_nonce(nonce: u64) -> Vec<u8> {
+    let nonce_hash = env::sha256(format!("{}", nonce).as_bytes());
+    owner_keypair().sign(&nonce_hash).to_bytes().to_vec()
+}
+
+#[test]
+fn test_withdraw_nonce_scope_is_separate_from_minting() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    register_storage(&mut context, &mut contract, &[owner_implicit_account()]);
+    contract.badge_mint_test(1.into(), owner_implicit_account());
+    contract.badge_mint_test(1.into(), owner_implicit_account());
+    contract.badge_mint_test(1.into(), owner_implicit_account());
+
+    contract.withdraw(
+        owner_public_key(),
+        accounts(2),
+        "1:1".to_string(),
+        sign_nonce(5),
+        Some(5),
+    );
+    contract.withdraw(
+        owner_public_key(),
+        accounts(2),
+        "1:2".to_string(),
+        sign_nonce(3),
+        Some(3),
+    );
+    assert_eq!(contract.nft_supply_for_owner(accounts(2)), 2.into());
+    assert!(contract.is_nonce_used(owner_implicit_account(), 2, 3));
+    assert_eq!(contract.get_nonce(&owner_implicit_account()), 5);
+
+    // a mint voucher can still use a nonce consumed by a withdrawal
+    let mut voucher = sample_voucher("mint_badge", 1, owner_implicit_account(), 1);
+    voucher.nonce = 3;
+    contract.mint_badge(
+        1,
+        owner_implicit_account(),
+        voucher.clone(),
+        sign_voucher(&voucher),
+    );
+    assert_eq!(
+        contract.nft_supply_for_owner(owner_implicit_account()),
+        2.into()
+    );
+}
+
+#[test]
+#[should_panic(expected = "Nonce 5 was already used")]
+fn test_withdraw_nonce_replayed() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), owner_implicit_account());
+    contract.badge_mint_test(1.into(), owner_implicit_account());
+
+    contract.withdraw(
+        owner_public_key(),
+        accounts(2),
+        "1:1".to_string(),
+        sign_nonce(5),
+        Some(5),
+    );
+    contract.withdraw(
+        owner_public_key(),
+        accounts(2),
+        "1:2".to_string(),
+        sign_nonce(5),
+        Some(5),
+    );
+}
+
+#[test]
+fn test_nonces_expire_below_window() {
+    let (_, mut contract) = setup_contract_with_series();
+    let account_id = accounts(1);
+    contract.internal_use_nonce(&account_id, NonceScope::OWNER, 2);
+    contract.internal_use_nonce(&account_id, NonceScope::OWNER, 200);
+    assert!(!contract.is_nonce_used(account_id.clone(), 2, 100));
+
+    contract.internal_use_nonce(&account_id, NonceScope::OWNER, 1_200);
+    assert_eq!(contract.get_nonce(&account_id), 1_200);
+    // nonces more than NONCE_WINDOW below the highest one expired
+    assert!(contract.is_nonce_used(account_id.clone(), 2, 100));
+    assert!(contract.is_nonce_used(account_id.clone(), 2, 200));
+    assert!(!contract.is_nonce_used(account_id.clone(), 2, 180));
+    assert!(!contract.is_nonce_used(account_id.clone(), 1, 100));
+    // the words that only held expired nonces were removed
+    assert!(contract
+        .used_nonces
+        .get(&(account_id.clone(), 2, 0))
+        .is_none());
+    assert!(contract.used_nonces.get(&(account_id, 2, 1)).is_some());
+}
+
+#[test]
+#[should_panic(expected = "Nonce 100 was already used or expired")]
+fn test_expired_nonce_used() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.internal_use_nonce(&accounts(1), NonceScope::OWNER, 1_200);
+    contract.internal_use_nonce(&accounts(1), NonceScope::OWNER, 100);
+}
+
+#[test]
+fn test_nft_mint_by_approved_minter() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.add_approved_minter(accounts(1));
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(ONE_NEAR)
+        .build());
+    let token_ids = contract.nft_mint(1, accounts(2), 2, Some("launch drop".to_string()));
+
+    assert_eq!(token_ids, vec!["1:1".to_string(), "1:2".to_string()]);
+    assert_eq!(contract.nft_supply_for_owner(accounts(2)), 2.into());
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"charlie","token_ids":["1:1","1:2"],"memo":"launch drop"}]}"#
+        ]
+    );
+
+    // whatever wasn't used for storage is refunded to the minter
+    let receipts = get_created_receipts();
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].receiver_id, accounts(1));
+}
+
+#[test]
+fn test_nft_mint_by_series_minter() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.add_series_minter(1, accounts(2));
+    assert_eq!(contract.get_series_minters(1), vec![accounts(2)]);
+
+    testing_env!(context
+        .predecessor_account_id(accounts(2))
+        .attached_deposit(ONE_NEAR)
+        .build());
+    contract.nft_mint(1, accounts(3), 1, None);
+    assert_eq!(contract.nft_supply_for_owner(accounts(3)), 1.into());
+}
+
+#[test]
+#[should_panic(expected = "only approved minters can mint")]
+fn test_nft_mint_by_removed_series_minter() {
+    let (mut context, mut contract) = setup_contract_with_creator_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.add_series_minter(1, accounts(2));
+    contract.remove_series_minter(1, accounts(2));
+
+    testing_env!(context
+        .predecessor_account_id(accounts(2))
+        .attached_deposit(ONE_NEAR)
+        .build());
+    contract.nft_mint(1, accounts(3), 1, None);
+}
+
+#[test]
+#[should_panic(expected = "only approved minters can mint")]
+fn test_nft_mint_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(ONE_NEAR)
+        .build());
+    contract.nft_mint(1, accounts(2), 1, None);
+}
+
+#[test]
+#[should_panic(expected = "Must attach")]
+fn test_nft_mint_without_storage_deposit() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    testing_env!(context.attached_deposit(1).build());
+    contract.storage_unregister(None);
+
+    testing_env!(context.attached_deposit(0).build());
+    contract.nft_mint(1, accounts(2), 1, None);
+}
+
+#[test]
+fn test_nft_airdrop() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_max_per_account(1, Some(2));
+
+    testing_env!(context.attached_deposit(ONE_NEAR).build());
+    let results = contract.nft_airdrop(
+        1,
+        vec![
+            (accounts(1), 1),
+            (accounts(2), 0),
+            (accounts(3), 1),
+            (accounts(1), 1),
+            (accounts(1), 1),
+        ],
+        Some("spring drop".to_string()),
+    );
+
+    let token_ids: Vec<Vec<String>> = results
+        .iter()
+        .map(|result| result.token_ids.clone())
+        .collect();
+    assert_eq!(
+        token_ids,
+        vec![
+            vec!["1:1".to_string()],
+            vec![],
+            vec!["1:2".to_string()],
+            vec!["1:3".to_string()],
+            vec![]
+        ]
+    );
+    assert_eq!(
+        results[1].error,
+        Some("Amount must be greater than 0".to_string())
+    );
+    assert_eq!(
+        results[4].error,
+        Some("Mint limit per account reached for this series".to_string())
+    );
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 2.into());
+
+    // a single log with one entry per owner
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"bob","token_ids":["1:1","1:3"],"memo":"spring drop"},{"owner_id":"danny","token_ids":["1:2"],"memo":"spring drop"}]}"#
+        ]
+    );
+}
+
+#[test]
+fn test_nft_airdrop_over_copies() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    // the sample series has 5 copies
+    testing_env!(context.attached_deposit(ONE_NEAR).build());
+    let results = contract.nft_airdrop(
+        1,
+        vec![(accounts(1), 4), (accounts(2), 2), (accounts(3), 1)],
+        None,
+    );
+
+    assert_eq!(results[0].token_ids.len(), 4);
+    assert_eq!(
+        results[1].error,
+        Some("Only 1 copies left in the series".to_string())
+    );
+    assert_eq!(results[2].token_ids, vec!["1:5".to_string()]);
+}
+
+#[test]
+fn test_mint_logs_are_chunked() {
+    testing_env!(get_context(accounts(0)).build());
+    let token_ids = |from: u64, to: u64| {
+        (from..=to)
+            .map(|edition| format!("1:{}", edition))
+            .collect()
+    };
+    crate::internal::log_nft_mints(vec![
+        crate::NftMintLog {
+            owner_id: accounts(1).to_string(),
+            token_ids: token_ids(1, 110),
+            memo: None,
+        },
+        crate::NftMintLog {
+            owner_id: accounts(2).to_string(),
+            token_ids: token_ids(111, 115),
+            memo: None,
+        },
+    ]);
+
+    // the second log continues bob's tokens and holds all of charlie's
+    let logs = get_logs();
+    assert_eq!(logs.len(), 2);
+    assert!(logs[0].contains(r#""token_ids":["1:1","#) && logs[0].contains(r#""1:100"]}]}"#));
+    assert!(logs[1].contains(r#"[{"owner_id":"bob","token_ids":["1:101","#));
+    assert!(logs[1].contains(r#"{"owner_id":"charlie","token_ids":["1:111","#));
+}
+
+#[test]
+#[should_panic(expected = "Series is paused")]
+fn test_nft_airdrop_paused_series() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.set_series_status(1, 3);
+
+    testing_env!(context.attached_deposit(ONE_NEAR).build());
+    contract.nft_airdrop(1, vec![(accounts(1), 1)], None);
+}
+
+#[test]
+#[should_panic(expected = "only approved minters can mint")]
+fn test_nft_airdrop_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(ONE_NEAR)
+        .build());
+    contract.nft_airdrop(1, vec![(accounts(1), 1)], None);
+}
+
+#[test]
+fn test_batch_mint_resumes_with_job() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    // only enough gas for part of the batch
+    testing_env!(context
+        .prepaid_gas(near_sdk::Gas(20_000_000_000_000))
+        .build());
+    let voucher = sample_voucher("batch_mint", 1, accounts(1), 5);
+    let job_id = contract
+        .batch_mint(
+            1,
+            5,
+            accounts(1),
+            voucher.clone(),
+            sign_voucher(&voucher),
+            None,
+        )
+        .expect("the batch should not fit");
+    let job = contract.get_job(job_id).unwrap();
+    assert!(job.processed > 0 && job.remaining > 0);
+    assert_eq!(job.processed + job.remaining, 5);
+    assert_eq!(contract.get_pending_jobs(None, None).len(), 1);
+    assert_eq!(
+        contract.nft_supply_for_owner(accounts(1)),
+        U128(job.processed.into())
+    );
+
+    // any account can continue the job
+    testing_env!(context
+        .predecessor_account_id(accounts(2))
+        .prepaid_gas(near_sdk::Gas(300_000_000_000_000))
+        .build());
+    assert_eq!(contract.continue_job(job_id), None);
+    assert!(contract.get_job(job_id).is_none());
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 5.into());
+}
+
+#[test]
+fn test_batch_burn_fewer_tokens_than_requested() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), accounts(1));
+    contract.badge_mint_test(1.into(), accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    let job_id = contract.batch_burn(
+        1,
+        Some(5),
+        owner_public_key(),
+        vec![],
+        Some(accounts(1)),
+        None,
+    );
+
+    assert_eq!(job_id, None);
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 0.into());
+}
+
+#[test]
+fn test_batch_withdraw() {
+    let (_, mut contract) = setup_contract_with_series();
+    for _ in 0..3 {
+        contract.badge_mint_test(1.into(), owner_implicit_account());
+    }
+
+    let job_id =
+        contract.batch_withdraw(1, 2, owner_public_key(), accounts(2), sign_nonce(1), None);
+
+    assert_eq!(job_id, None);
+    assert_eq!(contract.nft_supply_for_owner(accounts(2)), 2.into());
+    assert_eq!(
+        contract.nft_supply_for_owner(owner_implicit_account()),
+        1.into()
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not enough gas attached to process any item")]
+fn test_batch_mint_without_enough_gas() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context
+        .prepaid_gas(near_sdk::Gas(10_000_000_000_000))
+        .build());
+    let voucher = sample_voucher("batch_mint", 1, accounts(1), 5);
+    contract.batch_mint(
+        1,
+        5,
+        accounts(1),
+        voucher.clone(),
+        sign_voucher(&voucher),
+        None,
+    );
+}
+
+#[test]
+fn test_cancel_job() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    testing_env!(context
+        .prepaid_gas(near_sdk::Gas(20_000_000_000_000))
+        .build());
+    let voucher = sample_voucher("batch_mint", 1, accounts(1), 5);
+    let job_id = contract
+        .batch_mint(
+            1,
+            5,
+            accounts(1),
+            voucher.clone(),
+            sign_voucher(&voucher),
+            None,
+        )
+        .expect("the batch should not fit");
+
+    // the series can't be minted anymore, so the job can't finish
+    contract.set_paused(1, true);
+    contract.cancel_job(job_id);
+    assert!(contract.get_job(job_id).is_none());
+    assert!(contract.get_pending_jobs(None, None).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: missing role")]
+fn test_cancel_job_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    testing_env!(context
+        .prepaid_gas(near_sdk::Gas(20_000_000_000_000))
+        .build());
+    let voucher = sample_voucher("batch_mint", 1, accounts(1), 5);
+    let job_id = contract
+        .batch_mint(
+            1,
+            5,
+            accounts(1),
+            voucher.clone(),
+            sign_voucher(&voucher),
+            None,
+        )
+        .expect("the batch should not fit");
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.cancel_job(job_id);
+}
+
+#[test]
+#[should_panic(expected = "No job")]
+fn test_continue_unknown_job() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.continue_job(1);
+}
+
+#[test]
+fn test_series_ids_are_sequential() {
+    let (_, mut contract) = setup_contract_with_series();
+    assert_eq!(
+        contract.create_series(1, sample_token_metadata(), None, None, None, None),
+        2
+    );
+    assert_eq!(
+        contract.create_series(2, sample_token_metadata(), None, None, None, None),
+        3
+    );
+}
+
+// state of the first release: series 1 and 3 exist, series 3 has editions 1 and 2 owned by charlie.
+// The owner's implicit account used nonces up to 3.
+fn write_legacy_state() {
+    use crate::{LegacyContract, LegacySeries, SeriesType, StorageKey, Token};
+    use near_sdk::borsh::BorshSerialize;
+    use near_sdk::collections::{LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet};
+
+    let mut series_by_id = UnorderedMap::new(StorageKey::SeriesById.try_to_vec().unwrap());
+    let mut tokens_by_id = UnorderedMap::new(StorageKey::TokensById.try_to_vec().unwrap());
+    for (series_id, editions) in [(1u64, vec![]), (3u64, vec![1, 2])] {
+        let mut tokens = UnorderedSet::new(format!("legacy-series-{}", series_id).into_bytes());
+        for edition in editions {
+            let token_id = format!("{}:{}", series_id, edition);
+            tokens.insert(&token_id);
+            tokens_by_id.insert(
+                &token_id,
+                &Token {
+                    series_id,
+                    owner_id: accounts(2),
+                    approved_account_ids: HashMap::new(),
+                    next_approval_id: 0,
+                },
+            );
+        }
+        series_by_id.insert(
+            &series_id,
+            &LegacySeries {
+                metadata: sample_token_metadata(),
+                royalty: None,
+                tokens,
+                price: None,
+                owner_id: accounts(0),
+                series_type: SeriesType::LIMITED,
+            },
+        );
+    }
+
+    let mut nonces = LookupMap::new(StorageKey::Nonces.try_to_vec().unwrap());
+    nonces.insert(&owner_implicit_account(), &3);
+
+    let mut approved_creators = LookupSet::new(StorageKey::ApprovedCreators.try_to_vec().unwrap());
+    approved_creators.insert(&accounts(0));
+    env::state_write(&LegacyContract {
+        owner_id: accounts(0),
+        owner_public_key: owner_public_key(),
+        approved_minters: LookupSet::new(StorageKey::ApprovedMinters.try_to_vec().unwrap()),
+        approved_creators,
+        series_by_id,
+        tokens_by_id,
+        tokens_per_owner: LookupMap::new(StorageKey::TokensPerOwner.try_to_vec().unwrap()),
+        owner_tokens_per_series: UnorderedMap::new(
+            StorageKey::OwnerTokensPerSeries.try_to_vec().unwrap(),
+        ),
+        metadata: LazyOption::new(StorageKey::NFTContractMetadata.try_to_vec().unwrap(), None),
+        nonces,
+    });
+}
+
+#[test]
+fn test_migrate_from_legacy_state() {
+    let mut context = get_context(accounts(0));
+    context.current_account_id(accounts(0));
+    testing_env!(context.build());
+    write_legacy_state();
+
+    let mut contract = Contract::migrate();
+    register_storage(&mut context, &mut contract, &[accounts(0), accounts(1)]);
+
+    assert_eq!(contract.get_state_version(), 2);
+    assert_eq!(contract.get_series_details(3).unwrap().status, 2);
+    assert_eq!(contract.series_supply_stats(3).minted, 2.into());
+    assert_eq!(contract.contract_stats().minted, 2.into());
+    // legacy tokens are read in the current layout
+    let token = contract.nft_token("3:2".to_string()).unwrap();
+    assert_eq!(token.owner_id, accounts(2));
+    assert_eq!(token.series_id, 3);
+    assert_eq!(
+        contract.get_signing_keys()[0].public_key,
+        owner_public_key()
+    );
+    // series ids continue after the highest one in use, editions after the minted tokens
+    assert_eq!(
+        contract.create_series(1, sample_token_metadata(), None, None, None, None),
+        4
+    );
+    let voucher = sample_voucher("mint_badge", 3, accounts(1), 1);
+    contract.mint_badge(3, accounts(1), voucher.clone(), sign_voucher(&voucher));
+    assert!(contract.nft_token("3:3".to_string()).is_some());
+    // nonces consumed by the first release stay used in every scope
+    assert_eq!(contract.get_nonce(&owner_implicit_account()), 3);
+    assert!(contract.is_nonce_used(owner_implicit_account(), 1, 3));
+    assert!(contract.is_nonce_used(owner_implicit_account(), 2, 1));
+    assert!(!contract.is_nonce_used(owner_implicit_account(), 2, 4));
+}
+
+#[test]
+#[should_panic(expected = "Nonce 1 was already used or expired")]
+fn test_migrated_nonce_replayed() {
+    let mut context = get_context(accounts(0));
+    context.current_account_id(accounts(0));
+    testing_env!(context.build());
+    write_legacy_state();
+
+    let mut contract = Contract::migrate();
+    register_storage(&mut context, &mut contract, &[owner_implicit_account()]);
+    let mut voucher = sample_voucher("mint_badge", 3, owner_implicit_account(), 1);
+    voucher.nonce = 4;
+    contract.mint_badge(
+        3,
+        owner_implicit_account(),
+        voucher.clone(),
+        sign_voucher(&voucher),
+    );
+
+    // a withdraw signature the owner published before the upgrade
+    contract.withdraw(
+        owner_public_key(),
+        accounts(5),
+        "3:3".to_string(),
+        sign_nonce(1),
+        Some(1),
+    );
+}
+
+#[test]
+fn test_new_contract_is_current_state_version() {
+    let (_, contract) = setup_contract_with_series();
+    assert_eq!(contract.get_state_version(), crate::CURRENT_STATE_VERSION);
+}
+
+#[test]
+fn test_migrate_current_state_keeps_it() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), accounts(1));
+    env::state_write(&contract);
+
+    let contract = Contract::migrate();
+    assert_eq!(contract.get_state_version(), crate::CURRENT_STATE_VERSION);
+    assert_eq!(contract.series_supply_stats(1).minted, 1.into());
+    assert!(contract.nft_token("1:1".to_string()).is_some());
+}
+
+#[test]
+#[should_panic(expected = "Unknown state version 9")]
+fn test_migrate_unknown_state_version() {
+    use near_sdk::borsh::BorshSerialize;
+
+    let (_, contract) = setup_contract_with_series();
+    env::state_write(&contract);
+    env::storage_write(b"STATE_VERSION", &9u16.try_to_vec().unwrap());
+    Contract::migrate();
+}
+
+#[test]
+fn test_upgrade_deploys_code_and_migrates() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    testing_env!(context
+        .prepaid_gas(near_sdk::Gas(300_000_000_000_000))
+        .build());
+    let _ = contract.internal_upgrade(vec![1, 2, 3]);
+
+    let receipts = get_created_receipts();
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].receiver_id, env::current_account_id());
+    assert_eq!(receipts[0].actions.len(), 2);
+    assert_eq!(
+        receipts[0].actions[0],
+        VmAction::DeployContract {
+            code: vec![1, 2, 3]
+        }
+    );
+    match &receipts[0].actions[1] {
+        VmAction::FunctionCall {
+            function_name,
+            args,
+            gas,
+            ..
+        } => {
+            assert_eq!(function_name, "migrate");
+            assert_eq!(args, b"{}");
+            assert!(gas.0 > 0);
+        }
+        action => panic!("Unexpected action {:?}", action),
+    }
+}
+
+#[test]
+#[should_panic(expected = "only contract owner")]
+fn test_upgrade_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    let _ = contract.internal_upgrade(vec![1, 2, 3]);
+}
+
+#[test]
+fn test_burn_deletes_token() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), accounts(1));
+    contract.badge_mint_test(1.into(), accounts(1));
+    let storage_before_burn = env::storage_usage();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.batch_burn(
+        1,
+        Some(1),
+        owner_public_key(),
+        vec![],
+        Some(accounts(1)),
+        None,
+    );
+
+    assert!(env::storage_usage() < storage_before_burn);
+    assert_eq!(contract.nft_total_supply(), 1.into());
+    assert_eq!(contract.nft_supply_for_series(1), 1.into());
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 1.into());
+    assert_eq!(contract.get_series_details(1).unwrap().burned, 1);
+}
+
+#[test]
+fn test_supply_stats() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.create_series(1, sample_token_metadata(), None, None, None, None);
+    contract.badge_mint_test(1.into(), accounts(1));
+    contract.badge_mint_test(1.into(), accounts(1));
+    contract.badge_mint_test(2.into(), accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.batch_burn(
+        1,
+        Some(1),
+        owner_public_key(),
+        vec![],
+        Some(accounts(1)),
+        None,
+    );
+
+    let series_stats = contract.series_supply_stats(1);
+    assert_eq!(series_stats.minted, 2.into());
+    assert_eq!(series_stats.burned, 1.into());
+    assert_eq!(series_stats.circulating, 1.into());
+
+    let contract_stats = contract.contract_stats();
+    assert_eq!(contract_stats.series_count, 2);
+    assert_eq!(contract_stats.minted, 3.into());
+    assert_eq!(contract_stats.burned, 1.into());
+    assert_eq!(contract_stats.circulating, 2.into());
+}
+
+#[test]
+fn test_owner_receives_tokens_after_burning_all() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.batch_burn(1, None, owner_public_key(), vec![], Some(accounts(1)), None);
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 0.into());
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.badge_mint_test(1.into(), accounts(1));
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 1.into());
+    assert_eq!(
+        contract.owner_nft_tokens_for_series_count(1, accounts(1)),
+        1.into()
+    );
+}
+
+#[test]
+#[should_panic(expected = "Limit reached")]
+fn test_burned_copies_are_not_minted_again() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    let mut metadata = sample_token_metadata();
+    metadata.copies = Some(1);
+    contract.update_series_metadata(1, metadata, None);
+    contract.badge_mint_test(1.into(), accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.batch_burn(1, None, owner_public_key(), vec![], Some(accounts(1)), None);
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.badge_mint_test(1.into(), accounts(1));
+}
+
+// park tokens in the burn account, as burns did before tokens were deleted
+fn park_tokens(contract: &mut Contract, owner_id: &AccountId, token_ids: &[&str]) {
+    let burn_account_id = AccountId::new_unchecked("unrecoverable_burn_account".to_string());
+    for token_id in token_ids {
+        contract.internal_transfer_unrestricted(
+            owner_id,
+            &burn_account_id,
+            &token_id.to_string(),
+            None,
+            None,
+        );
+    }
+}
+
+#[test]
+fn test_burn_parked_tokens() {
+    let (_, mut contract) = setup_contract_with_series();
+    for _ in 0..3 {
+        contract.badge_mint_test(1.into(), accounts(1));
+    }
+    park_tokens(&mut contract, &accounts(1), &["1:1", "1:2"]);
+
+    assert_eq!(contract.burn_parked_tokens(Some(1)), 1);
+    assert_eq!(contract.burn_parked_tokens(None), 0);
+    assert_eq!(contract.burn_parked_tokens(None), 0);
+
+    assert_eq!(contract.nft_total_supply(), 1.into());
+    assert_eq!(contract.get_series_details(1).unwrap().burned, 2);
+    assert!(contract.nft_token("1:3".to_string()).is_some());
+}
+
+#[test]
+#[should_panic(expected = "only contract owner")]
+fn test_burn_parked_tokens_by_non_owner() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.burn_parked_tokens(None);
+}
+
+#[test]
+fn test_storage_deposit() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    let min = contract.storage_balance_bounds().min.0;
+    assert!(contract.storage_balance_of(accounts(4)).is_none());
+
+    testing_env!(context.attached_deposit(ONE_NEAR).build());
+    let balance = contract.storage_deposit(Some(accounts(4)), Some(true));
+    assert_eq!(balance.total, U128(min));
+    assert_eq!(balance.available, U128(0));
+
+    let balance = contract.storage_deposit(Some(accounts(4)), None);
+    assert_eq!(balance.total, U128(min + ONE_NEAR));
+    assert_eq!(balance.available, U128(ONE_NEAR));
+}
+
+#[test]
+#[should_panic(expected = "to register the account")]
+fn test_storage_deposit_below_minimum() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.attached_deposit(1).build());
+    contract.storage_deposit(Some(accounts(4)), None);
+}
+
+#[test]
+fn test_storage_withdraw() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    let min = contract.storage_balance_bounds().min.0;
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(1)
+        .build());
+    let balance = contract.storage_withdraw(Some(U128(ONE_NEAR / 2)));
+    assert_eq!(balance.total, U128(ONE_NEAR / 2));
+
+    let balance = contract.storage_withdraw(None);
+    assert_eq!(balance.total, U128(min));
+    assert_eq!(balance.available, U128(0));
+}
+
+#[test]
+#[should_panic(expected = "The amount is greater than the available storage balance")]
+fn test_storage_withdraw_more_than_available() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(1)
+        .build());
+    contract.storage_withdraw(Some(U128(ONE_NEAR)));
+}
+
+#[test]
+fn test_voucher_mint_charges_receiver_storage() {
+    let (_, mut contract) = setup_contract_with_series();
+    let owner_balance = contract.storage_balance_of(accounts(0)).unwrap().total;
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    contract.mint_badge(1, accounts(1), voucher.clone(), sign_voucher(&voucher));
+
+    let balance = contract.storage_balance_of(accounts(1)).unwrap();
+    assert!(balance.total.0 < ONE_NEAR);
+    assert_eq!(
+        contract.storage_balance_of(accounts(0)).unwrap().total,
+        owner_balance
+    );
+}
+
+#[test]
+fn test_voucher_mint_charges_nonce_storage() {
+    let (_, mut contract) = setup_contract_with_series();
+    let balance_before = contract.storage_balance_of(accounts(1)).unwrap().total.0;
+    let storage_before = env::storage_usage();
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    contract.mint_badge(1, accounts(1), voucher.clone(), sign_voucher(&voucher));
+
+    // the voucher's nonce is paid for along with the token
+    let storage_used = env::storage_usage() - storage_before;
+    assert_eq!(
+        contract.storage_balance_of(accounts(1)).unwrap().total.0,
+        balance_before - env::storage_byte_cost() * u128::from(storage_used)
+    );
+}
+
+#[test]
+fn test_add_series_minter_charges_series_owner_storage() {
+    let (_, mut contract) = setup_contract_with_series();
+    let owner_balance = contract.storage_balance_of(accounts(0)).unwrap().total;
+
+    contract.add_series_minter(1, accounts(2));
+    contract.add_series_minting_key(1, owner_public_key());
+
+    assert!(contract.storage_balance_of(accounts(0)).unwrap().total.0 < owner_balance.0);
+}
+
+#[test]
+#[should_panic(expected = "to cover storage")]
+fn test_add_claim_codes_without_storage_balance() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    testing_env!(context.attached_deposit(1).build());
+    contract.storage_unregister(None);
+
+    testing_env!(context.attached_deposit(0).build());
+    contract.add_claim_codes(1, vec![env::sha256(b"code-1").into()]);
+}
+
+#[test]
+fn test_burn_refunds_storage_payer() {
+    let (context, mut contract) = setup_contract_with_series();
+    contract.set_series_storage_sponsorship(1, true);
+    let voucher = sample_voucher("mint_badge", 1, owner_implicit_account(), 1);
+    contract.mint_badge(
+        1,
+        owner_implicit_account(),
+        voucher.clone(),
+        sign_voucher(&voucher),
+    );
+    let sponsor_balance = contract.storage_balance_of(accounts(0)).unwrap().total;
+
+    testing_env!(context.build());
+    contract.nft_burn(
+        "1:1".to_string(),
+        owner_public_key(),
+        sign_nonce(1),
+        Some(1),
+    );
+
+    // the sponsor paid for the token, so it gets the storage back instead of the burner
+    assert!(contract.storage_balance_of(accounts(0)).unwrap().total.0 > sponsor_balance.0);
+    assert!(get_created_receipts().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "to cover storage")]
+fn test_voucher_mint_to_unregistered_receiver() {
+    let (_, mut contract) = setup_contract_with_series();
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(4), 1);
+    contract.mint_badge(1, accounts(4), voucher.clone(), sign_voucher(&voucher));
+}
+
+#[test]
+fn test_sponsored_series_storage() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_series_storage_sponsorship(1, true);
+    assert!(contract.get_series_details(1).unwrap().storage_sponsored);
+    let sponsor_balance = contract.storage_balance_of(accounts(0)).unwrap().total;
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(4), 1);
+    contract.mint_badge(1, accounts(4), voucher.clone(), sign_voucher(&voucher));
+
+    assert_eq!(contract.nft_supply_for_owner(accounts(4)), 1.into());
+    assert!(contract.storage_balance_of(accounts(0)).unwrap().total.0 < sponsor_balance.0);
+}
+
+#[test]
+fn test_create_series_charges_creator_storage() {
+    let (_, mut contract) = setup_contract_with_series();
+    let creator_balance = contract.storage_balance_of(accounts(0)).unwrap().total;
+
+    contract.create_series(1, sample_token_metadata(), None, None, None, None);
+
+    assert!(contract.storage_balance_of(accounts(0)).unwrap().total.0 < creator_balance.0);
+}
+
+#[test]
+#[should_panic(expected = "Can't unregister an account that holds tokens without force")]
+fn test_storage_unregister_with_tokens() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), accounts(1));
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(1)
+        .build());
+    contract.storage_unregister(None);
+}
+
+#[test]
+fn test_set_paused() {
+    let (context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.build());
+    contract.set_paused(1, true);
+    contract.set_paused(4, true);
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"contract_pause_update","data":[{"feature":1,"paused":true}]}"#,
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"contract_pause_update","data":[{"feature":4,"paused":true}]}"#,
+        ]
+    );
+    assert!(contract.is_paused(1));
+    let pause_state = contract.get_pause_state();
+    assert!(pause_state.mint && pause_state.withdraw);
+    assert!(!pause_state.transfer && !pause_state.burn && !pause_state.create_series);
+
+    contract.set_paused(1, false);
+    assert!(!contract.is_paused(1));
+    contract.badge_mint_test(1.into(), accounts(1));
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), 1.into());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: missing role")]
+fn test_set_paused_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.set_paused(1, true);
+}
+
+#[test]
+#[should_panic(expected = "Minting is paused")]
+fn test_mint_while_paused() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_paused(1, true);
+
+    let voucher = sample_voucher("mint_badge", 1, accounts(1), 1);
+    contract.mint_badge(1, accounts(1), voucher.clone(), sign_voucher(&voucher));
+}
+
+#[test]
+fn test_series_mintability_while_paused() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_paused(1, true);
+
+    let mintability = contract.get_series_mintability(1);
+    assert!(!mintability.mintable);
+    assert_eq!(mintability.reason, Some("Minting is paused".to_string()));
+
+    contract.set_paused(1, false);
+    assert!(contract.get_series_mintability(1).mintable);
+}
+
+#[test]
+#[should_panic(expected = "Transfers are paused")]
+fn test_transfer_while_paused() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), accounts(1));
+    contract.set_paused(2, true);
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(1)
+        .build());
+    contract.nft_transfer(accounts(2), "1:1".to_string(), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Burning is paused")]
+fn test_burn_while_paused() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), accounts(1));
+    contract.set_paused(3, true);
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.batch_burn(1, None, owner_public_key(), vec![], Some(accounts(1)), None);
+}
+
+#[test]
+#[should_panic(expected = "Withdrawals are paused")]
+fn test_withdraw_while_paused() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.badge_mint_test(1.into(), owner_implicit_account());
+    contract.set_paused(4, true);
+
+    contract.withdraw(
+        owner_public_key(),
+        accounts(2),
+        "1:1".to_string(),
+        sign_nonce(1),
+        None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Series creation is paused")]
+fn test_create_series_while_paused() {
+    let (_, mut contract) = setup_contract_with_series();
+    contract.set_paused(5, true);
+
+    contract.create_series(1, sample_token_metadata(), None, None, None, None);
+}
+
+#[test]
+fn test_grant_and_revoke_roles() {
+    let (context, mut contract) = setup_contract_with_series();
+    assert_eq!(contract.get_role_members(2, None, None), vec![accounts(0)]);
+    assert_eq!(contract.get_account_roles(accounts(0)), vec![2, 3]);
+
+    testing_env!(context.build());
+    contract.grant_role(3, accounts(1));
+    contract.grant_role(3, accounts(2));
+    contract.revoke_role(3, accounts(1));
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"role_granted","data":[{"role":3,"account_id":"bob"}]}"#,
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"role_granted","data":[{"role":3,"account_id":"charlie"}]}"#,
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"role_revoked","data":[{"role":3,"account_id":"bob"}]}"#,
+        ]
+    );
+
+    assert_eq!(
+        contract.get_role_members(3, Some(U128(1)), Some(1)),
+        vec![accounts(2)]
+    );
+    assert!(contract.is_approved_minter(accounts(2)));
+    assert!(!contract.has_role(3, accounts(1)));
+    assert!(contract.get_account_roles(accounts(1)).is_empty());
+}
+
+#[test]
+fn test_admin_manages_roles() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.grant_role(1, accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.grant_role(2, accounts(2));
+    assert!(contract.is_approved_creator(accounts(2)));
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.create_series(1, sample_token_metadata(), None, None, None, None);
+    assert_eq!(
+        contract.get_series_details(2).unwrap().owner_id,
+        accounts(2)
+    );
+}
+
+#[test]
+#[should_panic(expected = "only the contract owner or an admin can manage roles")]
+fn test_admin_cannot_grant_admin() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.grant_role(1, accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.grant_role(1, accounts(2));
+}
+
+#[test]
+#[should_panic(expected = "only the contract owner or an admin can manage roles")]
+fn test_grant_role_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.grant_role(3, accounts(1));
+}
+
+#[test]
+fn test_pauser_and_moderator_roles() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.grant_role(4, accounts(1));
+    contract.grant_role(5, accounts(2));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.set_paused(1, true);
+    assert!(contract.is_paused(1));
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.set_series_status(1, 3);
+    assert_eq!(contract.get_series_details(1).unwrap().status, 3);
+}
+
+#[test]
+fn test_migrate_legacy_roles() {
+    use crate::StorageKey;
+    use near_sdk::borsh::BorshSerialize;
+    use near_sdk::collections::LookupSet;
+
+    let (_, mut contract) = setup_contract_with_series();
+    let mut legacy_minters: LookupSet<AccountId> =
+        LookupSet::new(StorageKey::ApprovedMinters.try_to_vec().unwrap());
+    legacy_minters.insert(&accounts(1));
+    legacy_minters.insert(&accounts(2));
+
+    assert_eq!(
+        contract.migrate_legacy_roles(3, vec![accounts(1), accounts(2), accounts(3)]),
+        2
+    );
+    assert_eq!(
+        contract.get_role_members(3, None, None),
+        vec![accounts(0), accounts(1), accounts(2)]
+    );
+    assert!(!legacy_minters.contains(&accounts(1)));
+    // accounts are only moved once
+    assert_eq!(contract.migrate_legacy_roles(3, vec![accounts(1)]), 0);
+}
+
+#[test]
+fn test_transfer_contract_ownership() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.build());
+    contract.propose_contract_owner(accounts(1));
+    assert_eq!(contract.get_pending_contract_owner(), Some(accounts(1)));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.accept_contract_owner(None);
+    assert_eq!(
+        get_logs(),
+        vec![
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"role_revoked","data":[{"role":2,"account_id":"alice"}]}"#,
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"role_revoked","data":[{"role":3,"account_id":"alice"}]}"#,
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"role_granted","data":[{"role":2,"account_id":"bob"}]}"#,
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"role_granted","data":[{"role":3,"account_id":"bob"}]}"#,
+            r#"EVENT_JSON:{"standard":"gateway","version":"1.0.0","event":"contract_owner_transferred","data":[{"old_owner_id":"alice","new_owner_id":"bob"}]}"#,
+        ]
+    );
+    assert!(contract.get_account_roles(accounts(0)).is_empty());
+    assert_eq!(contract.get_account_roles(accounts(1)), vec![2, 3]);
+    assert_eq!(contract.get_contract_owner(), accounts(1));
+    assert_eq!(contract.get_pending_contract_owner(), None);
+
+    // the new owner can run privileged calls, the signing keys are kept
+    contract.set_paused(1, true);
+    assert!(contract.get_signing_keys()[0].active);
+}
+
+#[test]
+fn test_accept_contract_owner_with_new_signing_key() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.propose_contract_owner(accounts(1));
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .block_index(42)
+        .build());
+    contract.accept_contract_owner(Some(creator_public_key()));
+
+    let signing_keys = contract.get_signing_keys();
+    assert_eq!(signing_keys.len(), 2);
+    assert!(!signing_keys[0].active);
+    assert_eq!(signing_keys[1].key_id, "bob@42");
+    assert_eq!(contract.owner_public_key(), Some(creator_public_key()));
+}
+
+#[test]
+#[should_panic(expected = "only contract owner")]
+fn test_previous_contract_owner_loses_access() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.propose_contract_owner(accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.accept_contract_owner(None);
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.add_signing_key("backup".to_string(), creator_public_key(), None);
+}
+
+#[test]
+#[should_panic(expected = "only approved minters can mint")]
+fn test_previous_contract_owner_cannot_mint() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.propose_contract_owner(accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.accept_contract_owner(None);
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    contract.nft_mint(1, accounts(2), 1, None);
+}
+
+#[test]
+#[should_panic(expected = "only the proposed owner can accept the contract")]
+fn test_accept_contract_owner_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.propose_contract_owner(accounts(1));
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.accept_contract_owner(None);
+}
+
+#[test]
+#[should_panic(expected = "only the proposed owner can accept the contract")]
+fn test_cancel_owner_proposal() {
+    let (mut context, mut contract) = setup_contract_with_series();
+    contract.propose_contract_owner(accounts(1));
+    contract.cancel_owner_proposal();
+    assert_eq!(contract.get_pending_contract_owner(), None);
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.accept_contract_owner(None);
+}
+
+#[test]
+#[should_panic(expected = "only contract owner")]
+fn test_propose_contract_owner_by_other_account() {
+    let (mut context, mut contract) = setup_contract_with_series();
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.propose_contract_owner(accounts(1));
+}
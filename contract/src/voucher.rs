@@ -0,0 +1,94 @@
+use ed25519_dalek::Verifier;
+
+use crate::*;
+
+/// A mint voucher is the payload that the owner's backend signs to authorize a signature gated mint.
+/// The signature is produced over `sha256(borsh(voucher))` so that every field is bound to it:
+/// a voucher issued for one contract, method, series, receiver or amount cannot be reused for another.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintVoucher {
+    // Account the contract is deployed on. Prevents replaying a voucher against another deployment
+    pub contract_id: AccountId,
+    // Method the voucher can be redeemed with, e.g. "mint_badge" or "batch_mint"
+    pub method: String,
+    // Series the tokens will be minted from
+    pub series_id: SeriesId,
+    // Account that will receive the tokens
+    pub receiver_id: AccountId,
+    // Number of tokens that can be minted with the voucher
+    pub amount: u32,
+    // Nonce of the receiver the voucher consumes, vouchers can be redeemed in any order
+    pub nonce: u64,
+    // When the voucher stops being valid, Unix epoch in milliseconds
+    pub expires_at: u64,
+}
+
+impl MintVoucher {
+    /// The message that gets signed: sha256 of the borsh encoded voucher
+    pub fn hash(&self) -> Vec<u8> {
+        env::sha256(&self.try_to_vec().unwrap())
+    }
+}
+
+impl Contract {
+    /// Ensure the voucher matches the call it is redeemed with, hasn't expired, uses a nonce the
+    /// receiver hasn't used yet and was signed by one of the owner's active signing keys or one of the series minting keys.
+    /// The receiver's nonce is consumed on success.
+    pub(crate) fn internal_use_mint_voucher(
+        &mut self,
+        voucher: &MintVoucher,
+        signature: &[u8],
+        method: &str,
+        series_id: SeriesId,
+        receiver_id: &AccountId,
+        amount: u32,
+    ) {
+        require!(
+            voucher.contract_id == env::current_account_id(),
+            "Voucher was issued for another contract"
+        );
+        require!(
+            voucher.method == method,
+            format!("Voucher was issued for method {}", voucher.method)
+        );
+        require!(
+            voucher.series_id == series_id,
+            "Voucher was issued for another series"
+        );
+        require!(
+            &voucher.receiver_id == receiver_id,
+            "Voucher was issued for another receiver"
+        );
+        require!(
+            voucher.amount == amount,
+            "Voucher was issued for another amount"
+        );
+        require!(
+            env::block_timestamp_ms() <= voucher.expires_at,
+            "Voucher expired"
+        );
+
+        // verify that the voucher was signed by an active owner key or a key of the series.
+        //thereby granting `receiver_id` permission to mint
+        let series = self.series_by_id.get(&series_id).expect("Not a series");
+        let signature = ed25519_dalek::Signature::try_from(signature)
+            .expect("Signature should be a valid array of 64 bytes [13, 254, 123, ...]");
+        let message = voucher.hash();
+        let is_signed_by_allowed_key = self
+            .internal_active_signing_keys()
+            .into_iter()
+            .chain(series.minting_keys.iter())
+            .any(|public_key| {
+                parse_public_key(&public_key)
+                    .verify(&message, &signature)
+                    .is_ok()
+            });
+        require!(
+            is_signed_by_allowed_key,
+            "Unauthorized: invalid voucher signature"
+        );
+
+        self.internal_use_nonce(receiver_id, NonceScope::MINT, voucher.nonce);
+    }
+}
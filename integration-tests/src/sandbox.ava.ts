@@ -2,7 +2,7 @@ import { Worker, NearAccount, NEAR, } from 'near-workspaces';
 import anyTest, { TestFn } from 'ava';
 import path from "path";
 import { createHash } from 'node:crypto'
-import { authorizedBatchNFTMint, authorizedNFBatchTBurn, authorizedNFTBurn, authorizedNFTMint, createBadgeCollection, createBadgeCollectionRaw } from './utils';
+import { authorizedBatchNFTMint, authorizedNFBatchTBurn, authorizedNFTBurn, authorizedNFTMint, createBadgeCollection, createBadgeCollectionRaw, signMintVoucher } from './utils';
 
 
 const test = anyTest as TestFn<{
@@ -40,6 +40,13 @@ test.beforeEach(async (t) => {
     initialBalance: NEAR.parse("100 N").toJSON(),
   });
 
+  // receivers pay for the storage of the tokens minted to them
+  for (const account of [alice, bob, charlie]) {
+    await account.call(root, "storage_deposit", {}, {
+      attachedDeposit: NEAR.parse("1 N").toJSON(),
+    });
+  }
+
   t.context.worker = worker;
   t.context.accounts = { root, contract: root, alice, bob, charlie };
 
@@ -77,7 +84,7 @@ test("should not allow unauthorized Badge creation", async (t) => {
 
   const result = await createBadgeCollectionRaw(alice, contract)
 
-  t.regex(result.receiptFailureMessages.join("\n"), /Method create_series is private+/);
+  t.regex(result.receiptFailureMessages.join("\n"), /only approved creators can add a new badge collection/);
 })
 test('should create a new badge collection', async (t) => {
   const { root, contract, } = t.context.accounts;
@@ -103,7 +110,18 @@ test('should create a new badge collection', async (t) => {
       },
       royalty: null,
       owner_id: 'test.near',
-      badge_type: 1
+      series_type: 1,
+      price: null,
+      pending_owner_id: null,
+      status: 2,
+      mint_starts_at: null,
+      mint_ends_at: null,
+      soulbound: false,
+      max_per_account: null,
+      unique: false,
+      allowlist_root: null,
+      burned: 0,
+      storage_sponsored: false
     }
   ]
 
@@ -120,35 +138,37 @@ test('should create a new badge collection', async (t) => {
 
 })
 
+test('should allow an approved creator to create a badge collection', async (t) => {
+  const { root, contract, alice } = t.context.accounts;
 
+  await root.call(contract, "add_approved_creator", { account_id: alice });
 
-test('should not allow unauthorized nft mint', async (t) => {
-  const { _, contract, alice } = t.context.accounts;
+  await createBadgeCollection(alice, contract)
 
-  const seriesId = 1;
-  //get current user nonce
-  const nonce = await alice.call(contract, "get_nonce", {
-    account_id: alice.accountId
-  });
+  const series: any = await root.view("get_series");
+
+  t.assert(series.length === 1);
+  t.assert(series[0].owner_id === alice.accountId);
+  t.assert(series[0].series_type === 1);
+})
 
 
-  //sign nonce using alice account(admin)
-  const hash = createHash('sha256');
 
-  const nextNonce: number = parseInt(nonce as any) + 1
-  const keyPair = await alice.getKey();
+test('should not allow unauthorized nft mint', async (t) => {
+  const { root, contract, alice } = t.context.accounts;
 
+  await createBadgeCollection(root, contract)
 
-  const message = nextNonce;
-  hash.update(message.toString())
-  const hashedMessage = hash.digest()
-  const signedMessage = keyPair?.sign(hashedMessage);
+  const seriesId = 1;
+  //voucher signed by alice instead of the contract owner
+  const { voucher, signature } = await signMintVoucher(alice, alice, contract, "mint_badge", seriesId, 1)
 
   // try to mint nft using wrong permission 
   const result = await alice.callRaw(contract, "mint_badge", {
-    id: seriesId,
+    series_id: seriesId,
     receiver_id: alice.accountId,
-    signature: Array.from(signedMessage!.signature)
+    voucher,
+    signature
   })
 
   t.regex(result.receiptFailureMessages.join("\n"), /Unauthorized+/);
@@ -329,7 +349,7 @@ test("reject update Badge media update for unauthorized acct", async (t) => {
     media: newMediaUrl,
     media_hash: (newMediaHash)
   })
-  t.regex(result.receiptFailureMessages.join("\n"), /Smart contract panicked: only approved creators can update metadata+/)
+  t.regex(result.receiptFailureMessages.join("\n"), /Smart contract panicked: only the series owner can update the series+/)
 
 
 })
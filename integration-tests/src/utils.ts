@@ -1,13 +1,13 @@
-import { NearAccount } from "near-workspaces";
+import { NEAR, NearAccount } from "near-workspaces";
 import { createHash } from "node:crypto";
 
 export async function createBadgeCollection(
     user: NearAccount,
     contract: NearAccount,
-    badge_type: number = 1
+    series_type: number = 1
 ) {
     const new_badge_payload = {
-        badge_type,
+        series_type,
         metadata: {
             title: 'Blue badge',
             description: "first level badge in the gateway nft collection",
@@ -21,16 +21,18 @@ export async function createBadgeCollection(
     return await user.call(
         contract,
         "create_series",
-        new_badge_payload
+        new_badge_payload,
+        // the creator pays for the storage of the series
+        { attachedDeposit: NEAR.parse("0.1 N").toJSON() }
     )
 }
 export async function createBadgeCollectionRaw(
     user: NearAccount,
     contract: NearAccount,
-    badge_type: number = 1
+    series_type: number = 1
 ) {
     const new_badge_payload = {
-        badge_type,
+        series_type,
         metadata: {
             title: 'Blue badge',
             description: "first level badge in the gateway nft collection",
@@ -44,40 +46,100 @@ export async function createBadgeCollectionRaw(
     return await user.callRaw(
         contract,
         "create_series",
-        new_badge_payload
+        new_badge_payload,
+        { attachedDeposit: NEAR.parse("0.1 N").toJSON() }
     )
 }
 
-export async function authorizedNFTMint(
-    root: NearAccount,
+// borsh encoding of the contract's `MintVoucher`, the payload the owner signs
+export function serializeMintVoucher(voucher: MintVoucher) {
+    const string = (value: string) => {
+        const bytes = Buffer.from(value, 'utf8');
+        const len = Buffer.alloc(4);
+        len.writeUInt32LE(bytes.length);
+        return Buffer.concat([len, bytes]);
+    };
+    const u32 = (value: number) => {
+        const buf = Buffer.alloc(4);
+        buf.writeUInt32LE(value);
+        return buf;
+    };
+    const u64 = (value: number) => {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(value));
+        return buf;
+    };
+
+    return Buffer.concat([
+        string(voucher.contract_id),
+        string(voucher.method),
+        u64(voucher.series_id),
+        string(voucher.receiver_id),
+        u32(voucher.amount),
+        u64(voucher.nonce),
+        u64(voucher.expires_at),
+    ]);
+}
+
+export interface MintVoucher {
+    contract_id: string,
+    method: string,
+    series_id: number,
+    receiver_id: string,
+    amount: number,
+    nonce: number,
+    expires_at: number,
+}
+
+export async function signMintVoucher(
+    signer: NearAccount,
     user: NearAccount,
     contract: NearAccount,
+    method: string,
     seriesId: number,
+    amount: number,
 ) {
     //get current user nonce
-    const nonce = await contract.call(contract, "get_nonce", {
+    const nonce = await contract.view("get_nonce", {
         account_id: user.accountId
     });
 
+    const voucher: MintVoucher = {
+        contract_id: contract.accountId,
+        method,
+        series_id: seriesId,
+        receiver_id: user.accountId,
+        amount,
+        nonce: parseInt(nonce as any) + 1,
+        //valid for the next 10 minutes
+        expires_at: Date.now() + 10 * 60 * 1000,
+    }
 
-    //sign nonce using alice account(admin)
-    const nextNonce: number = parseInt(nonce as any) + 1
     const hash = createHash('sha256');
+    hash.update(serializeMintVoucher(voucher))
+    const hashedMessage = hash.digest()
 
-    const keyPair = await root.getKey();
+    const keyPair = await signer.getKey();
+    const signedMessage = keyPair?.sign(hashedMessage);
 
+    return { voucher, signature: Array.from(signedMessage!.signature) }
+}
 
-    const message = nextNonce;
-    hash.update(message.toString())
-    const hashedMessage = hash.digest()
-    const signedMessage = keyPair?.sign(hashedMessage);
+export async function authorizedNFTMint(
+    root: NearAccount,
+    user: NearAccount,
+    contract: NearAccount,
+    seriesId: number,
+) {
+    //voucher signed by the contract owner
+    const { voucher, signature } = await signMintVoucher(root, user, contract, "mint_badge", seriesId, 1)
 
-    // try to mint nft using wrong permission 
     //make an authorized mint
     return await user.callRaw(contract, "mint_badge", {
-        id: seriesId,
+        series_id: seriesId,
         receiver_id: user.accountId,
-        signature: Array.from(signedMessage!.signature)
+        voucher,
+        signature
     })
 }
 
@@ -88,30 +150,15 @@ export async function authorizedBatchNFTMint(
     seriesId: number,
     amount: number
 ) {
-    //get current user nonce
-    const nonce = await contract.call(contract, "get_nonce", {
-        account_id: user.accountId
-    });
-
-
-    //sign nonce using alice account(admin)
-    const nextNonce: number = parseInt(nonce as any) + 1
-    const hash = createHash('sha256');
-
-    const keyPair = await root.getKey();
-
-
-    const message = nextNonce;
-    hash.update(message.toString())
-    const hashedMessage = hash.digest()
-    const signedMessage = keyPair?.sign(hashedMessage);
+    //voucher signed by the contract owner
+    const { voucher, signature } = await signMintVoucher(root, user, contract, "batch_mint", seriesId, amount)
 
-    // try to mint nft using wrong permission 
     //make an authorized mint
     return await user.callRaw(contract, "batch_mint", {
         series_id: seriesId,
         receiver_id: user.accountId,
-        signature: Array.from(signedMessage!.signature),
+        voucher,
+        signature,
         amount
     })
 }
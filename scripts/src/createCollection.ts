@@ -4,6 +4,7 @@ import { getAdminContract, loadAdminKeys, } from "./utils";
 
 import * as nearAPI from "near-api-js"
 import { getConfig } from "./config";
+import { NEAR } from "near-units";
 
 const { connect } = nearAPI;
 
@@ -59,7 +60,9 @@ async function createCollection() {
         methodName: "create_series",
         args: {
             ...new_badge_payload
-        }
+        },
+        // the creator pays for the storage of the series
+        attachedDeposit: NEAR.parse("0.1 N"),
     })
     const totalBadges = await adminAccount.viewFunctionV2(
         {
@@ -1,6 +1,6 @@
 require('dotenv').config()
 
-import { createAccessKeyAccount, generateAdminSignature, getUserContract, sleep, } from "./utils";
+import { createAccessKeyAccount, generateAdminMintVoucher, getUserContract, sleep, } from "./utils";
 
 import * as nearAPI from "near-api-js"
 import { getConfig } from "./config";
@@ -32,7 +32,7 @@ async function mintNFT() {
 
     const newUserAcct = await createAccessKeyAccount(nearConnection, keypair)
 
-    const signature = await generateAdminSignature(newUserAcct,);
+    const { voucher, signature } = await generateAdminMintVoucher(newUserAcct, userAccountId, "mint_badge", 11, 1);
 
 
     await newUserAcct.functionCall({
@@ -41,6 +41,7 @@ async function mintNFT() {
         args: {
             series_id: 11,
             receiver_id: userAccountId,
+            voucher,
             signature: signature
         },
         gas: 300000000000000,
@@ -1,6 +1,6 @@
 require('dotenv').config()
 
-import { createAccessKeyAccount, generateAdminSignature, getUserContract, sleep, } from "./utils";
+import { createAccessKeyAccount, generateAdminMintVoucher, getUserContract, sleep, } from "./utils";
 
 import * as nearAPI from "near-api-js"
 import { getConfig } from "./config";
@@ -30,7 +30,7 @@ async function mintNFTInBatch() {
 
     const newUserAcct = await createAccessKeyAccount(nearConnection, keypair)
 
-    const signature = await generateAdminSignature(newUserAcct,);
+    const { voucher, signature } = await generateAdminMintVoucher(newUserAcct, userAccountId, "batch_mint", 12, 5);
 
 
     await newUserAcct.functionCall({
@@ -40,6 +40,7 @@ async function mintNFTInBatch() {
             series_id: 12,
             amount: 5,
             receiver_id: userAccountId,
+            voucher,
             signature: signature
         },
         gas: 300000000000000,
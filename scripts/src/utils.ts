@@ -36,7 +36,47 @@ export async function createAccessKeyAccount(near: nearAPI.Near, key: nearAPI.Ke
     return new Account(near.connection, contractAccountId)
 }
 
-export async function generateAdminSignature(user: nearAPI.Account,) {
+// borsh encoding of the contract's `MintVoucher`, the payload the admin signs
+export function serializeMintVoucher(voucher: MintVoucher) {
+    const string = (value: string) => {
+        const bytes = Buffer.from(value, 'utf8');
+        const len = Buffer.alloc(4);
+        len.writeUInt32LE(bytes.length);
+        return Buffer.concat([len, bytes]);
+    };
+    const u32 = (value: number) => {
+        const buf = Buffer.alloc(4);
+        buf.writeUInt32LE(value);
+        return buf;
+    };
+    const u64 = (value: number) => {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(value));
+        return buf;
+    };
+
+    return Buffer.concat([
+        string(voucher.contract_id),
+        string(voucher.method),
+        u64(voucher.series_id),
+        string(voucher.receiver_id),
+        u32(voucher.amount),
+        u64(voucher.nonce),
+        u64(voucher.expires_at),
+    ]);
+}
+
+export interface MintVoucher {
+    contract_id: string,
+    method: string,
+    series_id: number,
+    receiver_id: string,
+    amount: number,
+    nonce: number,
+    expires_at: number,
+}
+
+export async function generateAdminMintVoucher(user: nearAPI.Account, receiverId: string, method: string, seriesId: number, amount: number) {
     const { secretKey } = await loadAdminKeys()
     const adminKeypair = KeyPair.fromString(secretKey)
 
@@ -46,23 +86,31 @@ export async function generateAdminSignature(user: nearAPI.Account,) {
         contractId: contractAccountId,
         methodName: "get_nonce",
         args: {
-            account_id: user.accountId
+            account_id: receiverId
         }
     })
 
-    const hash = createHash('sha256');
+    const voucher: MintVoucher = {
+        contract_id: contractAccountId,
+        method,
+        series_id: seriesId,
+        receiver_id: receiverId,
+        amount,
+        nonce: parseInt(userNonce as any) + 1,
+        //valid for the next 10 minutes
+        expires_at: Date.now() + 10 * 60 * 1000,
+    }
 
-    //sign nonce using alice account(admin)
-    const nextNonce: number = parseInt(userNonce as any) + 1
-    const message = nextNonce;
+    const hash = createHash('sha256');
 
-    hash.update(message.toString())
+    //sign voucher using admin account
+    hash.update(serializeMintVoucher(voucher))
 
     const hashedMessage = hash.digest()
 
     const signedMessage = adminKeypair?.sign(hashedMessage);
 
-    return Array.from(signedMessage!.signature)
+    return { voucher, signature: Array.from(signedMessage!.signature) }
 
 }
 export async function generateUserSignature(account: nearAPI.Account, userSeedPhrase: string,) {
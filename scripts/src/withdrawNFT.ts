@@ -1,6 +1,6 @@
 require('dotenv').config()
 
-import { createAccessKeyAccount, generateAdminSignature, generateUserSignature, getUserContract, sleep, } from "./utils";
+import { createAccessKeyAccount, generateUserSignature, getUserContract, sleep, } from "./utils";
 
 import * as nearAPI from "near-api-js"
 import { getConfig } from "./config";